@@ -0,0 +1,334 @@
+//! The `forest collect` subcommand.
+use clap::Args;
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::net::TcpListener;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tracing_forest::printer::Pretty;
+use tracing_forest::processor::forward::Handshake;
+use tracing_forest::tree::Tree;
+use tracing_forest::util::Level;
+use tracing_forest::Formatter;
+
+/// The address listened on if no `--tcp` or `--unix` address is given.
+const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+
+#[derive(Args, Debug)]
+pub struct Collect {
+    /// Listen for producers on a TCP address. Can be repeated.
+    #[arg(long = "tcp", value_name = "ADDR")]
+    tcp: Vec<String>,
+
+    /// Listen for producers on a Unix domain socket. Can be repeated.
+    #[cfg(unix)]
+    #[arg(long = "unix", value_name = "PATH")]
+    unix: Vec<PathBuf>,
+
+    /// Don't pretty-print received trees to stdout.
+    #[arg(long, short)]
+    quiet: bool,
+
+    /// Append received trees to a file as NDJSON, each in an object with the
+    /// name of the service that sent it, like `{"service":...,"tree":...}`.
+    #[arg(long, value_name = "FILE")]
+    json: Option<PathBuf>,
+
+    /// Print per-service statistics to stderr on shutdown.
+    #[arg(long)]
+    stats: bool,
+}
+
+/// A tree along with the name of the service that sent it.
+pub type Received = (Arc<str>, Tree);
+
+/// Where received trees are sent.
+struct Pipeline {
+    pretty: bool,
+    json: Option<File>,
+    stats: Option<BTreeMap<Arc<str>, Stats>>,
+}
+
+#[derive(Default)]
+struct Stats {
+    trees: usize,
+    spans: usize,
+    events: BTreeMap<Level, usize>,
+}
+
+impl Collect {
+    pub fn run(self) -> io::Result<()> {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?
+            .block_on(self.serve())
+    }
+
+    async fn serve(self) -> io::Result<()> {
+        let (tx, mut rx) = mpsc::unbounded_channel::<Received>();
+
+        let mut tcp = self.tcp.clone();
+        #[cfg(unix)]
+        let listening_on_unix = !self.unix.is_empty();
+        #[cfg(not(unix))]
+        let listening_on_unix = false;
+        if tcp.is_empty() && !listening_on_unix {
+            tcp.push(DEFAULT_ADDRESS.to_string());
+        }
+
+        listen(
+            &tcp,
+            #[cfg(unix)]
+            &self.unix,
+            &tx,
+        )
+        .await?;
+
+        drop(tx);
+
+        let mut pipeline = self.pipeline()?;
+
+        let ctrl_c = tokio::signal::ctrl_c();
+        tokio::pin!(ctrl_c);
+
+        loop {
+            tokio::select! {
+                Some((service, tree)) = rx.recv() => pipeline.process(service, tree),
+                _ = &mut ctrl_c => break,
+                else => break,
+            }
+        }
+
+        #[cfg(unix)]
+        for path in &self.unix {
+            let _ = std::fs::remove_file(path);
+        }
+
+        pipeline.finish();
+        Ok(())
+    }
+
+    fn pipeline(&self) -> io::Result<Pipeline> {
+        let json = match &self.json {
+            Some(path) => Some(OpenOptions::new().create(true).append(true).open(path)?),
+            None => None,
+        };
+
+        Ok(Pipeline {
+            pretty: !self.quiet,
+            json,
+            stats: if self.stats {
+                Some(BTreeMap::new())
+            } else {
+                None
+            },
+        })
+    }
+}
+
+/// Binds each address, and spawns tasks that send the trees received on them.
+pub async fn listen(
+    tcp: &[String],
+    #[cfg(unix)] unix: &[PathBuf],
+    tx: &UnboundedSender<Received>,
+) -> io::Result<()> {
+    for address in tcp {
+        let listener = TcpListener::bind(address).await?;
+        eprintln!("forest: listening on tcp://{}", listener.local_addr()?);
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        tokio::spawn(receive(stream, peer.to_string(), tx.clone()));
+                    }
+                    Err(err) => eprintln!("forest: failed to accept connection: {}", err),
+                }
+            }
+        });
+    }
+
+    #[cfg(unix)]
+    for path in unix {
+        remove_stale_socket(path)?;
+        let listener = UnixListener::bind(path)?;
+        eprintln!("forest: listening on unix://{}", path.display());
+        let tx = tx.clone();
+        let name = path.display().to_string();
+        tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        tokio::spawn(receive(stream, name.clone(), tx.clone()));
+                    }
+                    Err(err) => eprintln!("forest: failed to accept connection: {}", err),
+                }
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Removes a socket file left behind by a collector that didn't shut down
+/// cleanly, so that its path can be bound again.
+///
+/// Sockets that still accept connections are left alone, so binding them fails.
+#[cfg(unix)]
+fn remove_stale_socket(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::UnixStream;
+
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            if UnixStream::connect(path).is_err() {
+                std::fs::remove_file(path)?;
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Reads NDJSON trees from a connection, and sends them to the pipeline.
+///
+/// If the first line is a [`Handshake`], its service name is used to label
+/// the trees. Otherwise, the trees are labeled with the peer's address.
+async fn receive<R>(reader: R, peer: String, tx: UnboundedSender<Received>)
+where
+    R: AsyncRead + Unpin,
+{
+    let mut service: Arc<str> = peer.into();
+    let mut lines = BufReader::new(reader).lines();
+    let mut first = true;
+
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(err) => {
+                eprintln!("forest: [{}] connection failed: {}", service, err);
+                break;
+            }
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if std::mem::take(&mut first) {
+            if let Ok(handshake) = serde_json::from_str::<Handshake>(&line) {
+                service = handshake.service.into();
+                continue;
+            }
+        }
+
+        match serde_json::from_str::<Tree>(&line) {
+            Ok(tree) => {
+                if tx.send((service.clone(), tree)).is_err() {
+                    break;
+                }
+            }
+            Err(err) => eprintln!("forest: [{}] skipping invalid tree: {}", service, err),
+        }
+    }
+}
+
+impl Pipeline {
+    fn process(&mut self, service: Arc<str>, tree: Tree) {
+        if let Some(stats) = &mut self.stats {
+            stats.entry(service.clone()).or_default().record(&tree);
+        }
+
+        if self.pretty {
+            if let Err(err) = write_prefixed(&service, &tree) {
+                eprintln!("forest: failed to write tree: {}", err);
+            }
+        }
+
+        if let Some(json) = &mut self.json {
+            if let Err(err) = write_json(json, &service, &tree) {
+                eprintln!("forest: failed to write tree: {}", err);
+            }
+        }
+    }
+
+    fn finish(self) {
+        let stats = match self.stats {
+            Some(stats) => stats,
+            None => return,
+        };
+
+        let mut stderr = io::stderr().lock();
+        let _ = writeln!(
+            stderr,
+            "{:<24} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8}",
+            "SERVICE", "TREES", "SPANS", "TRACE", "DEBUG", "INFO", "WARN", "ERROR"
+        );
+        for (service, stats) in stats {
+            let events = |level| stats.events.get(&level).copied().unwrap_or(0);
+            let _ = writeln!(
+                stderr,
+                "{:<24} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8} {:>8}",
+                service,
+                stats.trees,
+                stats.spans,
+                events(Level::TRACE),
+                events(Level::DEBUG),
+                events(Level::INFO),
+                events(Level::WARN),
+                events(Level::ERROR),
+            );
+        }
+    }
+}
+
+impl Stats {
+    fn record(&mut self, tree: &Tree) {
+        self.trees += 1;
+        self.record_node(tree);
+    }
+
+    fn record_node(&mut self, tree: &Tree) {
+        match tree {
+            Tree::Event(event) => *self.events.entry(event.level()).or_default() += 1,
+            Tree::Span(span) => {
+                self.spans += 1;
+                for node in span.nodes() {
+                    self.record_node(node);
+                }
+            }
+        }
+    }
+}
+
+/// Appends a tree to the `--json` file, along with the service that sent it.
+fn write_json(file: &mut File, service: &str, tree: &Tree) -> io::Result<()> {
+    let record = serde_json::json!({ "service": service, "tree": tree });
+    let mut line = serde_json::to_string(&record)?;
+    line.push('\n');
+    file.write_all(line.as_bytes())
+}
+
+/// Pretty-prints a tree to stdout, with each line prefixed by the service name.
+fn write_prefixed(service: &str, tree: &Tree) -> io::Result<()> {
+    let string = Pretty.fmt(tree).map_err(io::Error::other)?;
+
+    let mut out = String::with_capacity(string.len());
+    for line in string.lines() {
+        out.push('[');
+        out.push_str(service);
+        out.push_str("] ");
+        out.push_str(line);
+        out.push('\n');
+    }
+
+    io::stdout().lock().write_all(out.as_bytes())
+}
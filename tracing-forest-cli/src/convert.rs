@@ -0,0 +1,83 @@
+//! The `forest convert` subcommand.
+use crate::filter::Filter;
+use crate::{input, view};
+use clap::Args;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tracing_forest::fmt_json::Converter;
+use tracing_forest::tree::Tree;
+
+#[derive(Args, Debug)]
+pub struct Convert {
+    /// Files of `tracing_subscriber::fmt` JSON logs. Reads from stdin if none
+    /// are given.
+    #[arg(value_name = "FILE")]
+    files: Vec<PathBuf>,
+
+    /// Pretty-print the trees instead of writing them as NDJSON.
+    #[arg(long)]
+    pretty: bool,
+
+    #[command(flatten)]
+    filter: Filter,
+}
+
+impl Convert {
+    pub fn run(self) -> io::Result<()> {
+        let stdout = io::stdout();
+        let mut out = stdout.lock();
+
+        let files = if self.files.is_empty() {
+            vec![PathBuf::from("-")]
+        } else {
+            self.files.clone()
+        };
+
+        for path in &files {
+            match self.convert(path, &mut out) {
+                Ok(()) => {}
+                // Stop quietly when piped into `head` or `less`.
+                Err(err) if err.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn convert(&self, path: &Path, out: &mut impl Write) -> io::Result<()> {
+        let reader = input::open(path)?;
+
+        // Each file is converted separately, since spans can't cross files.
+        let mut converter = Converter::new();
+        for (n, line) in input::lines_lossy(reader).enumerate() {
+            match converter.push(&line?) {
+                Ok(trees) => self.write(out, trees)?,
+                Err(err) => eprintln!(
+                    "forest: {}:{}: skipping invalid line: {}",
+                    path.display(),
+                    n + 1,
+                    err
+                ),
+            }
+        }
+        self.write(out, converter.finish())
+    }
+
+    fn write(&self, out: &mut impl Write, trees: Vec<Tree>) -> io::Result<()> {
+        for tree in trees {
+            let tree = match self.filter.apply(tree) {
+                Some(tree) => tree,
+                None => continue,
+            };
+
+            if self.pretty {
+                view::print(out, &tree)?;
+            } else {
+                serde_json::to_writer(&mut *out, &tree)?;
+                writeln!(out)?;
+            }
+        }
+        Ok(())
+    }
+}
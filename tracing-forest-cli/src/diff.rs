@@ -0,0 +1,132 @@
+//! The `forest diff` subcommand.
+use crate::filter::Filter;
+use crate::input;
+use clap::Args;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing_forest::diff::{Change, Profile, Stats};
+use tracing_forest::printer::DurationDisplay;
+
+#[derive(Args, Debug)]
+pub struct Diff {
+    /// NDJSON trees from the baseline run.
+    #[arg(value_name = "BEFORE")]
+    before: PathBuf,
+
+    /// NDJSON trees from the run to compare against the baseline.
+    #[arg(value_name = "AFTER")]
+    after: PathBuf,
+
+    /// Minimum change in mean duration, in percent, to report a path as a
+    /// regression or improvement.
+    #[arg(long, value_name = "PERCENT", default_value_t = 5.0)]
+    threshold: f64,
+
+    /// Also show paths whose mean duration changed less than the threshold.
+    #[arg(long)]
+    all: bool,
+
+    #[command(flatten)]
+    filter: Filter,
+}
+
+impl Diff {
+    pub fn run(self) -> io::Result<()> {
+        let before = self.profile(&self.before)?;
+        let after = self.profile(&self.after)?;
+        let diff = before.compare(&after);
+        let threshold = self.threshold / 100.0;
+
+        let stdout = io::stdout();
+        let mut out = stdout.lock();
+
+        let regressions = diff.regressions(threshold);
+        let improvements = diff.improvements(threshold);
+        let unchanged: Vec<&Change> = diff
+            .changes
+            .iter()
+            .filter(|change| change.mean_change().abs() <= threshold)
+            .collect();
+
+        section(&mut out, "Regressions", &regressions)?;
+        section(&mut out, "Improvements", &improvements)?;
+        if self.all {
+            section(&mut out, "Unchanged", &unchanged)?;
+        } else if !unchanged.is_empty() {
+            writeln!(
+                out,
+                "{} paths changed by less than {}% (use --all to show them)\n",
+                unchanged.len(),
+                self.threshold
+            )?;
+        }
+
+        for (name, paths) in [(&self.before, &diff.removed), (&self.after, &diff.added)] {
+            if paths.is_empty() {
+                continue;
+            }
+            writeln!(out, "Only in {}:", name.display())?;
+            for (path, stats) in paths {
+                writeln!(
+                    out,
+                    "  {}  (count {}, mean {})",
+                    path,
+                    stats.count,
+                    DurationDisplay(stats.mean)
+                )?;
+            }
+            writeln!(out)?;
+        }
+
+        Ok(())
+    }
+
+    fn profile(&self, path: &Path) -> io::Result<Profile> {
+        let mut profile = Profile::new();
+        for tree in input::read_trees(&[path.to_path_buf()]) {
+            if let Some(tree) = self.filter.apply(tree?) {
+                profile.add(&tree);
+            }
+        }
+        Ok(profile)
+    }
+}
+
+type Stat = fn(&Stats) -> Duration;
+
+/// The statistics shown for each changed path.
+const STATS: [(&str, Stat); 4] = [
+    ("mean", |stats| stats.mean),
+    ("p50", |stats| stats.p50),
+    ("p90", |stats| stats.p90),
+    ("p99", |stats| stats.p99),
+];
+
+fn section(out: &mut impl Write, title: &str, changes: &[&Change]) -> io::Result<()> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(out, "{}:", title)?;
+    for change in changes {
+        writeln!(out, "  {}", change.path)?;
+        writeln!(
+            out,
+            "    count  {:>9} -> {:<9}",
+            change.before.count, change.after.count
+        )?;
+
+        for (name, stat) in STATS {
+            writeln!(
+                out,
+                "    {:<5}  {:>9} -> {:<9}  {:+.1}%",
+                name,
+                DurationDisplay(stat(&change.before)).to_string(),
+                DurationDisplay(stat(&change.after)).to_string(),
+                change.relative_change(stat) * 100.0
+            )?;
+        }
+    }
+    writeln!(out)
+}
@@ -0,0 +1,138 @@
+//! Filters for selecting trees, shared by the viewing subcommands.
+use chrono::{DateTime, Utc};
+use clap::Args;
+use tracing_forest::tree::{Field, Tree};
+use tracing_forest::util::Level;
+use uuid::Uuid;
+
+#[derive(Args, Clone, Debug, Default)]
+pub struct Filter {
+    /// Only show trees containing a span or event with this UUID.
+    #[arg(long)]
+    uuid: Option<Uuid>,
+
+    /// Hide events less severe than LEVEL.
+    #[arg(long, value_name = "LEVEL")]
+    level: Option<Level>,
+
+    /// Only show trees whose root span has this name.
+    #[arg(long = "span", value_name = "NAME")]
+    span: Option<String>,
+
+    /// Only show trees containing an event with this tag or tag prefix, like
+    /// `security` or `security.critical`.
+    #[arg(long)]
+    tag: Option<String>,
+
+    /// Only show trees that started at or after this RFC 3339 time.
+    #[arg(long, value_name = "TIME", value_parser = parse_time)]
+    since: Option<DateTime<Utc>>,
+
+    /// Only show trees that started at or before this RFC 3339 time.
+    #[arg(long, value_name = "TIME", value_parser = parse_time)]
+    until: Option<DateTime<Utc>>,
+
+    /// Only show trees containing a span or event with this field value. Can
+    /// be repeated.
+    #[arg(long = "field", value_name = "KEY=VALUE", value_parser = parse_field)]
+    fields: Vec<(String, String)>,
+}
+
+impl Filter {
+    /// Returns the tree with hidden events removed if it passes the filter.
+    pub fn apply(&self, mut tree: Tree) -> Option<Tree> {
+        if let Some(level) = self.level {
+            if !prune(&mut tree, level) {
+                return None;
+            }
+        }
+
+        if let Some(name) = &self.span {
+            if tree.span().ok()?.name() != name {
+                return None;
+            }
+        }
+
+        let timestamp = match &tree {
+            Tree::Event(event) => event.timestamp(),
+            Tree::Span(span) => span.timestamp(),
+        };
+        if matches!(self.since, Some(since) if timestamp < since)
+            || matches!(self.until, Some(until) if timestamp > until)
+        {
+            return None;
+        }
+
+        if let Some(uuid) = self.uuid {
+            let matched = any(&tree, &mut |node| match node {
+                Tree::Event(event) => event.uuid() == uuid,
+                Tree::Span(span) => span.uuid() == uuid,
+            });
+            if !matched {
+                return None;
+            }
+        }
+
+        if let Some(tag) = &self.tag {
+            let matched = any(&tree, &mut |node| match node {
+                Tree::Event(event) => event
+                    .tag()
+                    .is_some_and(|t| t.to_string() == *tag || t.prefix() == Some(tag.as_str())),
+                Tree::Span(_) => false,
+            });
+            if !matched {
+                return None;
+            }
+        }
+
+        for (key, value) in &self.fields {
+            let has_field = |fields: &[Field]| {
+                fields.iter().any(|field| {
+                    field.key() == key
+                        && (field.value() == value || field.value() == format!("{:?}", value))
+                })
+            };
+            let matched = any(&tree, &mut |node| match node {
+                Tree::Event(event) => has_field(event.fields()),
+                Tree::Span(span) => has_field(span.fields()),
+            });
+            if !matched {
+                return None;
+            }
+        }
+
+        Some(tree)
+    }
+}
+
+/// Removes events less severe than `level`, returning whether the tree remains.
+fn prune(tree: &mut Tree, level: Level) -> bool {
+    match tree {
+        // `Level`s are ordered by verbosity, so more severe levels are smaller.
+        Tree::Event(event) => event.level() <= level,
+        Tree::Span(span) => {
+            span.nodes_mut().retain_mut(|node| prune(node, level));
+            true
+        }
+    }
+}
+
+/// Returns whether any node in the tree satisfies the predicate.
+fn any(tree: &Tree, f: &mut impl FnMut(&Tree) -> bool) -> bool {
+    f(tree)
+        || match tree {
+            Tree::Event(_) => false,
+            Tree::Span(span) => span.nodes().iter().any(|node| any(node, f)),
+        }
+}
+
+fn parse_time(s: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
+    DateTime::parse_from_rfc3339(s).map(|time| time.with_timezone(&Utc))
+}
+
+fn parse_field(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) => Ok((key.to_string(), value.to_string())),
+        None => Err(format!("expected KEY=VALUE, found `{}`", s)),
+    }
+}
@@ -0,0 +1,260 @@
+//! The `forest follow` subcommand.
+use crate::filter::Filter;
+use crate::input;
+use crate::view;
+use clap::Args;
+use std::fs::{self, File, Metadata};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tracing_forest::tree::Tree;
+
+#[derive(Args, Debug)]
+pub struct Follow {
+    /// NDJSON file to follow. If this is a directory, such as one written to
+    /// by a `RollingFile`, follows the most recently modified file in it.
+    #[arg(value_name = "PATH")]
+    path: PathBuf,
+
+    /// Print the trees already in the file before following it.
+    #[arg(long)]
+    from_start: bool,
+
+    /// How often to check for new trees, in milliseconds.
+    #[arg(long, value_name = "MS", default_value_t = 250)]
+    interval: u64,
+
+    #[command(flatten)]
+    filter: Filter,
+}
+
+/// A file being followed.
+struct Source {
+    path: PathBuf,
+    file: File,
+    id: Option<FileId>,
+    offset: u64,
+    line: usize,
+    /// Bytes of a line that hasn't been completely written yet.
+    partial: Vec<u8>,
+    /// Whether `partial` is the tail of a line whose start was skipped.
+    skip_partial: bool,
+}
+
+impl Follow {
+    pub fn run(self) -> io::Result<()> {
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        let interval = Duration::from_millis(self.interval);
+
+        let mut source = None;
+        let mut from_start = self.from_start;
+
+        loop {
+            if source.is_none() {
+                source = self.open(from_start)?;
+                // Files that appear after the first are always read in full.
+                from_start = true;
+            }
+
+            if let Some(current) = source.as_mut() {
+                // Rotation is checked before reading, so that the old file is
+                // read to the end before switching to the new one.
+                let rotated = self.is_rotated(current)?;
+
+                for tree in current.read()? {
+                    let tree = match self.filter.apply(tree) {
+                        Some(tree) => tree,
+                        None => continue,
+                    };
+
+                    match view::print(&mut stdout, &tree) {
+                        Ok(()) => {}
+                        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+                        Err(err) => return Err(err),
+                    }
+                }
+
+                if rotated {
+                    if !current.partial.is_empty() && !current.skip_partial {
+                        eprintln!(
+                            "forest: {}: discarding incomplete line after rotation",
+                            current.path.display()
+                        );
+                    }
+                    source = None;
+                    continue;
+                }
+            }
+
+            thread::sleep(interval);
+        }
+    }
+
+    /// Opens the file to follow, or returns `None` if it doesn't exist yet.
+    fn open(&self, from_start: bool) -> io::Result<Option<Source>> {
+        let path = match self.resolve()? {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(with_path(&path, err)),
+        };
+        let metadata = file.metadata()?;
+
+        let mut source = Source {
+            id: FileId::of(&metadata),
+            path,
+            offset: 0,
+            line: 0,
+            partial: Vec::new(),
+            skip_partial: false,
+            file,
+        };
+
+        if !from_start && metadata.len() > 0 {
+            // Start at the end, skipping the rest of a partially written line.
+            let mut last = [0];
+            source.file.seek(SeekFrom::Start(metadata.len() - 1))?;
+            source.file.read_exact(&mut last)?;
+            source.offset = metadata.len();
+            source.skip_partial = last[0] != b'\n';
+        }
+
+        Ok(Some(source))
+    }
+
+    /// Returns the path of the file that should currently be followed.
+    fn resolve(&self) -> io::Result<Option<PathBuf>> {
+        if !self.path.is_dir() {
+            return Ok(Some(self.path.clone()));
+        }
+
+        let mut newest: Option<(SystemTime, PathBuf)> = None;
+        for entry in fs::read_dir(&self.path).map_err(|err| with_path(&self.path, err))? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if !metadata.is_file() || is_compressed(&entry.path()) {
+                continue;
+            }
+
+            let modified = metadata.modified()?;
+            if newest
+                .as_ref()
+                .map_or(true, |(newest, _)| modified > *newest)
+            {
+                newest = Some((modified, entry.path()));
+            }
+        }
+
+        Ok(newest.map(|(_, path)| path))
+    }
+
+    /// Returns whether the followed file was truncated, replaced, or rotated.
+    ///
+    /// Truncated files are read again from the start in place.
+    fn is_rotated(&self, source: &mut Source) -> io::Result<bool> {
+        if self.resolve()?.as_deref() != Some(source.path.as_path()) {
+            return Ok(true);
+        }
+
+        let metadata = match fs::metadata(&source.path) {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(true),
+            Err(err) => return Err(err),
+        };
+
+        if FileId::of(&metadata) != source.id {
+            return Ok(true);
+        }
+
+        if metadata.len() < source.offset {
+            eprintln!("forest: {}: file truncated", source.path.display());
+            source.file.seek(SeekFrom::Start(0))?;
+            source.offset = 0;
+            source.line = 0;
+            source.partial.clear();
+            source.skip_partial = false;
+        }
+
+        Ok(false)
+    }
+}
+
+impl Source {
+    /// Reads the trees on lines that were completed since the last read.
+    fn read(&mut self) -> io::Result<Vec<Tree>> {
+        let read = self.file.read_to_end(&mut self.partial)?;
+        self.offset += read as u64;
+
+        let end = match self.partial.iter().rposition(|&b| b == b'\n') {
+            Some(end) => end + 1,
+            None => return Ok(Vec::new()),
+        };
+        let rest = self.partial.split_off(end);
+        let mut complete = mem::replace(&mut self.partial, rest);
+        complete.pop();
+
+        let mut lines = complete.split(|&b| b == b'\n');
+        if mem::take(&mut self.skip_partial) {
+            lines.next();
+        }
+
+        let mut trees = Vec::new();
+        for line in lines {
+            self.line += 1;
+            let parsed = match input::parse_line(&String::from_utf8_lossy(line)) {
+                Some(parsed) => parsed,
+                None => continue,
+            };
+
+            match parsed {
+                Ok(tree) => trees.push(tree),
+                Err(err) => eprintln!(
+                    "forest: {}:{}: skipping invalid tree: {}",
+                    self.path.display(),
+                    self.line,
+                    err
+                ),
+            }
+        }
+
+        Ok(trees)
+    }
+}
+
+/// Identifies a file independently of its path, to detect when it's replaced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct FileId {
+    dev: u64,
+    ino: u64,
+}
+
+impl FileId {
+    #[cfg(unix)]
+    fn of(metadata: &Metadata) -> Option<Self> {
+        use std::os::unix::fs::MetadataExt;
+        Some(FileId {
+            dev: metadata.dev(),
+            ino: metadata.ino(),
+        })
+    }
+
+    #[cfg(not(unix))]
+    fn of(_metadata: &Metadata) -> Option<Self> {
+        None
+    }
+}
+
+fn is_compressed(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "gz")
+}
+
+fn with_path(path: &Path, err: io::Error) -> io::Error {
+    io::Error::new(err.kind(), format!("{}: {}", path.display(), err))
+}
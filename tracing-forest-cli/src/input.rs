@@ -0,0 +1,119 @@
+//! Reading NDJSON trees from files or stdin.
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use tracing_forest::tree::Tree;
+
+/// Reads trees from each path in order, or from stdin if there are none.
+///
+/// A path of `-` also reads from stdin. Lines that aren't valid trees are
+/// reported to stderr and skipped.
+pub fn read_trees(paths: &[PathBuf]) -> impl Iterator<Item = io::Result<Tree>> {
+    let paths = if paths.is_empty() {
+        vec![PathBuf::from("-")]
+    } else {
+        paths.to_vec()
+    };
+
+    paths.into_iter().flat_map(|path| {
+        let trees: Box<dyn Iterator<Item = io::Result<Tree>>> = match open(&path) {
+            Ok(reader) => Box::new(parse_lines(path.display().to_string(), reader)),
+            Err(err) => Box::new(std::iter::once(Err(err))),
+        };
+        trees
+    })
+}
+
+/// Opens a file for reading, or stdin if the path is `-`.
+pub fn open(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    if path == Path::new("-") {
+        return Ok(Box::new(BufReader::new(io::stdin())));
+    }
+
+    match File::open(path) {
+        Ok(file) => Ok(Box::new(BufReader::new(file))),
+        Err(err) => Err(io::Error::new(
+            err.kind(),
+            format!("{}: {}", path.display(), err),
+        )),
+    }
+}
+
+/// Parses each nonempty line of a reader as a tree.
+///
+/// `name` identifies the reader when reporting invalid lines to stderr.
+pub fn parse_lines<R: BufRead>(name: String, reader: R) -> impl Iterator<Item = io::Result<Tree>> {
+    parse_lines_with(name, reader, |warning| eprintln!("forest: {}", warning))
+}
+
+/// Like [`parse_lines`], but passes warnings about invalid lines to `report`.
+pub fn parse_lines_with<R, F>(
+    name: String,
+    reader: R,
+    mut report: F,
+) -> impl Iterator<Item = io::Result<Tree>>
+where
+    R: BufRead,
+    F: FnMut(String),
+{
+    lines_lossy(reader)
+        .enumerate()
+        .filter_map(move |(n, line)| match line {
+            Err(err) => Some(Err(err)),
+            Ok(line) => match parse_line(&line)? {
+                Ok(tree) => Some(Ok(tree)),
+                Err(err) => {
+                    report(format!(
+                        "{}:{}: skipping invalid tree: {}",
+                        name,
+                        n + 1,
+                        err
+                    ));
+                    None
+                }
+            },
+        })
+}
+
+/// Returns the lines of a reader, replacing invalid UTF-8 instead of failing.
+///
+/// Trailing `\n` and `\r\n` line endings are removed, like [`BufRead::lines`].
+pub fn lines_lossy<R: BufRead>(mut reader: R) -> impl Iterator<Item = io::Result<String>> {
+    let mut buf = Vec::new();
+    std::iter::from_fn(move || {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) => None,
+            Ok(_) => {
+                if buf.ends_with(b"\n") {
+                    buf.pop();
+                    if buf.ends_with(b"\r") {
+                        buf.pop();
+                    }
+                }
+                Some(Ok(String::from_utf8_lossy(&buf).into_owned()))
+            }
+            Err(err) => Some(Err(err)),
+        }
+    })
+}
+
+/// Parses a line as a tree, or returns `None` if it's blank.
+///
+/// Lines written by `forest collect --json`, which wrap the tree in an object
+/// with the name of the service that sent it, are parsed as their tree.
+pub fn parse_line(line: &str) -> Option<serde_json::Result<Tree>> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+
+    Some(
+        serde_json::from_str(line).and_then(|mut value: serde_json::Value| {
+            if let Some(tree) = value.get_mut("tree") {
+                value = tree.take();
+            }
+            serde_json::from_value(value)
+        }),
+    )
+}
@@ -0,0 +1,65 @@
+//! Command-line tools for working with `tracing-forest` log trees.
+#![deny(warnings)]
+#![warn(unused_extern_crates)]
+
+use clap::{Parser, Subcommand};
+use std::process::ExitCode;
+
+mod collect;
+mod convert;
+mod diff;
+mod filter;
+mod follow;
+mod input;
+mod tui;
+mod view;
+
+/// View, collect, and analyze `tracing-forest` log trees.
+#[derive(Parser, Debug)]
+#[command(name = "forest", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Receive NDJSON trees from `Forward` processors over TCP or Unix sockets.
+    Collect(collect::Collect),
+
+    /// Pretty-print NDJSON trees from files or stdin.
+    View(view::View),
+
+    /// Pretty-print NDJSON trees as they're appended to a file, like `tail -f`.
+    Follow(follow::Follow),
+
+    /// Compare span durations between two recorded runs.
+    Diff(diff::Diff),
+
+    /// Convert `tracing_subscriber::fmt` JSON logs into NDJSON trees.
+    Convert(convert::Convert),
+
+    /// Browse NDJSON trees interactively in the terminal.
+    Tui(tui::Tui),
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Collect(collect) => collect.run(),
+        Command::View(view) => view.run(),
+        Command::Follow(follow) => follow.run(),
+        Command::Diff(diff) => diff.run(),
+        Command::Convert(convert) => convert.run(),
+        Command::Tui(tui) => tui.run(),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("forest: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
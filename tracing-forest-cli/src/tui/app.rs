@@ -0,0 +1,337 @@
+//! The state of the tree browser, independent of the terminal.
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing_forest::tree::{Span, Tree};
+use tracing_forest::util::Level;
+
+/// The position of a node, as the index of its tree followed by the index of
+/// each child leading to it.
+pub type Path = Vec<usize>;
+
+/// A loaded tree, along with the service that sent it, if it was received
+/// over a socket.
+pub struct Entry {
+    pub service: Option<Arc<str>>,
+    pub tree: Tree,
+}
+
+/// A visible line in the browser.
+pub struct Row {
+    pub path: Path,
+    /// For each ancestor below the root, whether it has siblings after it.
+    pub guides: Vec<bool>,
+    /// Whether the node has siblings after it.
+    pub has_next: bool,
+}
+
+#[derive(Default)]
+pub struct App {
+    pub entries: Vec<Entry>,
+    pub rows: Vec<Row>,
+    pub cursor: usize,
+    pub sort_by_duration: bool,
+    /// The search being typed, if any.
+    pub input: Option<String>,
+    /// The last submitted search.
+    pub search: Option<String>,
+    /// A message shown in the status line until the next key press.
+    pub status: Option<String>,
+    expanded: HashSet<Path>,
+}
+
+impl App {
+    /// Adds trees to the end of the browser.
+    pub fn extend(&mut self, entries: impl IntoIterator<Item = Entry>) {
+        let len = self.entries.len();
+        self.entries.extend(entries);
+        if self.entries.len() > len {
+            self.refresh();
+        }
+    }
+
+    /// Returns the node at a path.
+    pub fn node(&self, path: &[usize]) -> &Tree {
+        let mut tree = &self.entries[path[0]].tree;
+        for &index in &path[1..] {
+            match tree {
+                Tree::Span(span) => tree = &span.nodes()[index],
+                Tree::Event(_) => unreachable!("events have no children"),
+            }
+        }
+        tree
+    }
+
+    pub fn is_expanded(&self, path: &[usize]) -> bool {
+        self.expanded.contains(path)
+    }
+
+    /// Returns the root span of the tree containing a path, if it's a span.
+    pub fn root(&self, path: &[usize]) -> Option<&Span> {
+        self.entries[path[0]].tree.span().ok()
+    }
+
+    fn current(&self) -> Option<&Path> {
+        self.rows.get(self.cursor).map(|row| &row.path)
+    }
+
+    /// Recomputes the visible rows, keeping the cursor on the same node.
+    fn refresh(&mut self) {
+        let current = self.current().cloned();
+
+        let mut rows = Vec::new();
+        for index in 0..self.entries.len() {
+            let mut path = vec![index];
+            self.flatten(&mut path, &mut Vec::new(), false, &mut rows);
+        }
+        self.rows = rows;
+
+        if let Some(current) = current {
+            self.select(&current);
+        }
+        self.cursor = self.cursor.min(self.rows.len().saturating_sub(1));
+    }
+
+    fn flatten(
+        &self,
+        path: &mut Path,
+        guides: &mut Vec<bool>,
+        has_next: bool,
+        rows: &mut Vec<Row>,
+    ) {
+        rows.push(Row {
+            path: path.clone(),
+            guides: guides.clone(),
+            has_next,
+        });
+
+        if !self.is_expanded(path) {
+            return;
+        }
+
+        if let Tree::Span(span) = self.node(path) {
+            let order = self.order(span);
+            let is_root = path.len() == 1;
+            if !is_root {
+                guides.push(has_next);
+            }
+            for (i, &index) in order.iter().enumerate() {
+                path.push(index);
+                self.flatten(path, guides, i + 1 < order.len(), rows);
+                path.pop();
+            }
+            if !is_root {
+                guides.pop();
+            }
+        }
+    }
+
+    /// Returns the indices of a span's children in display order.
+    fn order(&self, span: &Span) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..span.nodes().len()).collect();
+        if self.sort_by_duration {
+            order.sort_by_key(|&index| match &span.nodes()[index] {
+                Tree::Span(child) => std::cmp::Reverse(child.total_duration()),
+                Tree::Event(_) => std::cmp::Reverse(Default::default()),
+            });
+        }
+        order
+    }
+
+    /// Returns every path in display order, including hidden ones.
+    fn walk(&self) -> Vec<Path> {
+        fn visit(app: &App, path: &mut Path, paths: &mut Vec<Path>) {
+            paths.push(path.clone());
+            if let Tree::Span(span) = app.node(path) {
+                for index in app.order(span) {
+                    path.push(index);
+                    visit(app, path, paths);
+                    path.pop();
+                }
+            }
+        }
+
+        let mut paths = Vec::new();
+        for index in 0..self.entries.len() {
+            visit(self, &mut vec![index], &mut paths);
+        }
+        paths
+    }
+
+    /// Moves the cursor to a path if it's visible.
+    fn select(&mut self, path: &[usize]) {
+        if let Some(row) = self.rows.iter().position(|row| row.path == path) {
+            self.cursor = row;
+        }
+    }
+
+    /// Expands the ancestors of a path and moves the cursor to it.
+    fn reveal(&mut self, path: &[usize]) {
+        for len in 1..path.len() {
+            self.expanded.insert(path[..len].to_vec());
+        }
+        self.refresh();
+        self.select(path);
+    }
+
+    pub fn move_by(&mut self, delta: isize) {
+        let last = self.rows.len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+    }
+
+    pub fn move_to_end(&mut self) {
+        self.cursor = self.rows.len().saturating_sub(1);
+    }
+
+    /// Expands or collapses the span under the cursor.
+    pub fn toggle(&mut self) {
+        if let Some(path) = self.current().cloned() {
+            if !self.expanded.remove(&path) {
+                self.expanded.insert(path);
+            }
+            self.refresh();
+        }
+    }
+
+    pub fn expand(&mut self) {
+        if let Some(path) = self.current().cloned() {
+            self.expanded.insert(path);
+            self.refresh();
+        }
+    }
+
+    /// Collapses the span under the cursor, or moves to its parent if it's
+    /// already collapsed.
+    pub fn collapse(&mut self) {
+        let path = match self.current().cloned() {
+            Some(path) => path,
+            None => return,
+        };
+
+        if self.expanded.remove(&path) {
+            self.refresh();
+        } else if path.len() > 1 {
+            self.select(&path[..path.len() - 1]);
+        }
+    }
+
+    /// Expands the span under the cursor and all of its descendants.
+    pub fn expand_all(&mut self) {
+        let path = match self.current().cloned() {
+            Some(path) => path,
+            None => return,
+        };
+
+        for descendant in self.walk() {
+            if descendant.starts_with(&path) && matches!(self.node(&descendant), Tree::Span(_)) {
+                self.expanded.insert(descendant);
+            }
+        }
+        self.refresh();
+    }
+
+    /// Collapses every span, leaving the cursor on its tree.
+    pub fn collapse_all(&mut self) {
+        let root = self.current().map(|path| vec![path[0]]);
+        self.expanded.clear();
+        self.refresh();
+        if let Some(root) = root {
+            self.select(&root);
+        }
+    }
+
+    pub fn toggle_sort(&mut self) {
+        self.sort_by_duration = !self.sort_by_duration;
+        self.refresh();
+    }
+
+    /// Moves to the next or previous error, expanding spans to reveal it.
+    pub fn next_error(&mut self, forward: bool) {
+        let found = self.find(forward, |tree| level(tree) == Level::ERROR);
+        if !found {
+            self.status = Some("No errors".to_string());
+        }
+    }
+
+    /// Submits the search being typed, and moves to the first match.
+    pub fn submit_search(&mut self) {
+        let query = self.input.take().unwrap_or_default().to_lowercase();
+        self.search = if query.is_empty() { None } else { Some(query) };
+        self.next_match(true);
+    }
+
+    /// Moves to the next or previous node matching the search.
+    pub fn next_match(&mut self, forward: bool) {
+        let query = match self.search.clone() {
+            Some(query) => query,
+            None => return,
+        };
+
+        if !self.find(forward, |tree| matches(tree, &query)) {
+            self.status = Some(format!("No matches for {:?}", query));
+        }
+    }
+
+    /// Returns whether a node matches the submitted search.
+    pub fn is_match(&self, path: &[usize]) -> bool {
+        self.search
+            .as_deref()
+            .is_some_and(|query| matches(self.node(path), query))
+    }
+
+    /// Moves to the next node after the cursor satisfying a predicate,
+    /// wrapping around, and returns whether one was found.
+    fn find(&mut self, forward: bool, predicate: impl Fn(&Tree) -> bool) -> bool {
+        let mut paths = self.walk();
+        if !forward {
+            paths.reverse();
+        }
+
+        let start = self
+            .current()
+            .and_then(|current| paths.iter().position(|path| path == current))
+            .map_or(0, |index| index + 1);
+
+        let len = paths.len();
+        let found = (0..len)
+            .map(|offset| &paths[(start + offset) % len])
+            .find(|path| predicate(self.node(path)))
+            .cloned();
+
+        match found {
+            Some(path) => {
+                self.reveal(&path);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn level(tree: &Tree) -> Level {
+    match tree {
+        Tree::Event(event) => event.level(),
+        Tree::Span(span) => span.level(),
+    }
+}
+
+/// Returns whether a node's name, message, tag, or fields contain a
+/// lowercase query.
+fn matches(tree: &Tree, query: &str) -> bool {
+    let contains = |text: &str| text.to_lowercase().contains(query);
+
+    let (text, fields) = match tree {
+        Tree::Event(event) => {
+            let tag = event.tag().map(|tag| tag.to_string());
+            if tag.as_deref().is_some_and(contains) {
+                return true;
+            }
+            (event.message().unwrap_or_default(), event.fields())
+        }
+        Tree::Span(span) => (span.name(), span.fields()),
+    };
+
+    contains(text)
+        || fields
+            .iter()
+            .any(|field| contains(field.key()) || contains(field.value()))
+}
@@ -0,0 +1,350 @@
+//! The `forest tui` subcommand.
+use crate::collect::{self, Received};
+use crate::filter::Filter;
+use crate::input;
+use app::{App, Entry, Row};
+use clap::Args;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+use tracing_forest::printer::DurationDisplay;
+use tracing_forest::tree::Tree;
+use tracing_forest::util::Level;
+use tracing_forest::Tag;
+
+mod app;
+
+/// Sent to the browser from background threads.
+#[allow(clippy::large_enum_variant)]
+enum Message {
+    Tree(Entry),
+    Status(String),
+}
+
+/// How long to wait for a key press before checking for new trees.
+const TICK: Duration = Duration::from_millis(100);
+
+const HELP: &str = "q quit  ↑↓ move  ⏎ toggle  ←→ collapse/expand  e/c all  \
+                    [/] errors  / search  n/N matches  s sort";
+
+#[derive(Args, Debug)]
+pub struct Tui {
+    /// NDJSON files to read trees from. Reads from stdin if none are given
+    /// and no sockets are listened on.
+    #[arg(value_name = "FILE")]
+    files: Vec<PathBuf>,
+
+    /// Listen for trees from `Forward` processors on a TCP address. Can be
+    /// repeated.
+    #[arg(long = "tcp", value_name = "ADDR")]
+    tcp: Vec<String>,
+
+    /// Listen for trees from `Forward` processors on a Unix domain socket.
+    /// Can be repeated.
+    #[cfg(unix)]
+    #[arg(long = "unix", value_name = "PATH")]
+    unix: Vec<PathBuf>,
+
+    #[command(flatten)]
+    filter: Filter,
+}
+
+impl Tui {
+    pub fn run(self) -> io::Result<()> {
+        if !io::stdout().is_terminal() {
+            return Err(io::Error::other("`forest tui` must be run in a terminal"));
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let _runtime = self.listen(&tx)?;
+
+        if !self.files.is_empty() || !self.is_listening() {
+            let files = self.files.clone();
+            thread::spawn(move || read_files(&files, tx));
+        }
+
+        let mut terminal = ratatui::try_init()?;
+        let result = self.browse(&mut terminal, rx);
+        ratatui::try_restore()?;
+
+        #[cfg(unix)]
+        for path in &self.unix {
+            let _ = std::fs::remove_file(path);
+        }
+
+        result
+    }
+
+    fn is_listening(&self) -> bool {
+        #[cfg(unix)]
+        return !self.tcp.is_empty() || !self.unix.is_empty();
+        #[cfg(not(unix))]
+        return !self.tcp.is_empty();
+    }
+
+    /// Starts listening on the sockets, if there are any, returning the
+    /// runtime that receives from them.
+    fn listen(&self, tx: &Sender<Message>) -> io::Result<Option<tokio::runtime::Runtime>> {
+        if !self.is_listening() {
+            return Ok(None);
+        }
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+
+        let (sockets_tx, mut sockets_rx) = tokio::sync::mpsc::unbounded_channel::<Received>();
+        runtime.block_on(collect::listen(
+            &self.tcp,
+            #[cfg(unix)]
+            &self.unix,
+            &sockets_tx,
+        ))?;
+
+        let tx = tx.clone();
+        runtime.spawn(async move {
+            while let Some((service, tree)) = sockets_rx.recv().await {
+                let service = Some(service);
+                if tx.send(Message::Tree(Entry { service, tree })).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Some(runtime))
+    }
+
+    fn browse(&self, terminal: &mut DefaultTerminal, rx: Receiver<Message>) -> io::Result<()> {
+        let mut app = App::default();
+        let mut list = ListState::default();
+
+        loop {
+            let mut entries = Vec::new();
+            for message in rx.try_iter() {
+                match message {
+                    Message::Tree(entry) => {
+                        if let Some(tree) = self.filter.apply(entry.tree) {
+                            entries.push(Entry { tree, ..entry });
+                        }
+                    }
+                    Message::Status(status) => app.status = Some(status),
+                }
+            }
+            app.extend(entries);
+
+            terminal.draw(|frame| draw(frame, &app, &mut list))?;
+
+            if !event::poll(TICK)? {
+                continue;
+            }
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !handle_key(&mut app, key) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Reads trees from files in the background.
+///
+/// Problems are shown in the status line, since writing to stderr would
+/// garble the screen.
+fn read_files(files: &[PathBuf], tx: Sender<Message>) {
+    let stdin = [PathBuf::from("-")];
+    let files = if files.is_empty() { &stdin[..] } else { files };
+
+    for path in files {
+        let reader = match input::open(path) {
+            Ok(reader) => reader,
+            Err(err) => {
+                let _ = tx.send(Message::Status(err.to_string()));
+                continue;
+            }
+        };
+
+        let warnings = tx.clone();
+        let report = move |warning| {
+            let _ = warnings.send(Message::Status(warning));
+        };
+
+        for tree in input::parse_lines_with(path.display().to_string(), reader, report) {
+            let message = match tree {
+                Ok(tree) => Message::Tree(Entry {
+                    service: None,
+                    tree,
+                }),
+                Err(err) => Message::Status(format!("{}: {}", path.display(), err)),
+            };
+            if tx.send(message).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Applies a key press, returning `false` if the browser should quit.
+fn handle_key(app: &mut App, key: KeyEvent) -> bool {
+    app.status = None;
+
+    if let Some(input) = &mut app.input {
+        match key.code {
+            KeyCode::Enter => app.submit_search(),
+            KeyCode::Esc => app.input = None,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            _ => {}
+        }
+        return true;
+    }
+
+    let page = 20;
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => return false,
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+        KeyCode::Down | KeyCode::Char('j') => app.move_by(1),
+        KeyCode::Up | KeyCode::Char('k') => app.move_by(-1),
+        KeyCode::PageDown => app.move_by(page),
+        KeyCode::PageUp => app.move_by(-page),
+        KeyCode::Home | KeyCode::Char('g') => app.cursor = 0,
+        KeyCode::End | KeyCode::Char('G') => app.move_to_end(),
+        KeyCode::Enter | KeyCode::Char(' ') => app.toggle(),
+        KeyCode::Right | KeyCode::Char('l') => app.expand(),
+        KeyCode::Left | KeyCode::Char('h') => app.collapse(),
+        KeyCode::Char('e') => app.expand_all(),
+        KeyCode::Char('c') => app.collapse_all(),
+        KeyCode::Char(']') => app.next_error(true),
+        KeyCode::Char('[') => app.next_error(false),
+        KeyCode::Char('/') => app.input = Some(String::new()),
+        KeyCode::Char('n') => app.next_match(true),
+        KeyCode::Char('N') => app.next_match(false),
+        KeyCode::Char('s') => app.toggle_sort(),
+        _ => {}
+    }
+    true
+}
+
+fn draw(frame: &mut Frame, app: &App, list: &mut ListState) {
+    let [main, status] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+
+    let mut title = format!(" forest: {} trees ", app.entries.len());
+    if app.sort_by_duration {
+        title.push_str("(sorted by duration) ");
+    }
+
+    let items: Vec<ListItem> = app.rows.iter().map(|row| row_line(app, row)).collect();
+    list.select((!app.rows.is_empty()).then_some(app.cursor));
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED)),
+        main,
+        list,
+    );
+
+    let status_line = match (&app.input, &app.status) {
+        (Some(input), _) => Line::from(format!("/{}", input)),
+        (None, Some(status)) => Line::from(status.as_str()).yellow(),
+        (None, None) => Line::from(HELP).dark_gray(),
+    };
+    frame.render_widget(Paragraph::new(status_line), status);
+}
+
+fn row_line<'a>(app: &'a App, row: &Row) -> ListItem<'a> {
+    let tree = app.node(&row.path);
+    let level = match tree {
+        Tree::Event(event) => event.level(),
+        Tree::Span(span) => span.level(),
+    };
+
+    let mut spans = vec![Span::styled(format!("{:<8} ", level), level_style(level))];
+
+    if row.path.len() == 1 {
+        if let Some(service) = &app.entries[row.path[0]].service {
+            spans.push(Span::raw(format!("[{}] ", service)).dark_gray());
+        }
+    }
+
+    let mut indent = String::new();
+    for &guide in &row.guides {
+        indent.push_str(if guide { "│  " } else { "   " });
+    }
+    if row.path.len() > 1 {
+        indent.push_str(if row.has_next { "┝━ " } else { "┕━ " });
+    }
+    spans.push(Span::raw(indent));
+
+    let matched = app.is_match(&row.path);
+    let highlight = |span: Span<'a>| {
+        if matched {
+            span.yellow()
+        } else {
+            span
+        }
+    };
+
+    match tree {
+        Tree::Event(event) => {
+            let tag = event.tag().unwrap_or_else(|| Tag::from(event.level()));
+            spans.push(Span::raw(format!("{} [{}]: ", tag.icon(), tag)));
+            spans.push(highlight(Span::raw(event.message().unwrap_or_default())));
+            for field in event.fields() {
+                spans.push(Span::raw(format!(" | {}: {}", field.key(), field.value())).dark_gray());
+            }
+        }
+        Tree::Span(span) => {
+            let marker = if span.nodes().is_empty() {
+                "  "
+            } else if app.is_expanded(&row.path) {
+                "▾ "
+            } else {
+                "▸ "
+            };
+            spans.push(Span::raw(marker));
+            spans.push(highlight(Span::raw(span.name()).bold()));
+
+            let total = span.total_duration();
+            let root = app
+                .root(&row.path)
+                .map_or(total, |root| root.total_duration());
+            let percent = if root.is_zero() {
+                100.0
+            } else {
+                100.0 * total.as_secs_f64() / root.as_secs_f64()
+            };
+            spans.push(Span::raw(format!(
+                " [ {} | {:.2}% ]",
+                DurationDisplay(total),
+                percent
+            )));
+
+            for field in span.fields() {
+                spans.push(Span::raw(format!(" {}: {}", field.key(), field.value())).dark_gray());
+            }
+        }
+    }
+
+    ListItem::new(Line::from(spans))
+}
+
+fn level_style(level: Level) -> Style {
+    let color = match level {
+        Level::TRACE => Color::Magenta,
+        Level::DEBUG => Color::Blue,
+        Level::INFO => Color::Green,
+        Level::WARN => Color::Yellow,
+        Level::ERROR => Color::Red,
+    };
+    Style::new().fg(color).bold()
+}
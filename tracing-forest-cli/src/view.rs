@@ -0,0 +1,49 @@
+//! The `forest view` subcommand.
+use crate::filter::Filter;
+use crate::input;
+use clap::Args;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use tracing_forest::printer::Pretty;
+use tracing_forest::tree::Tree;
+use tracing_forest::Formatter;
+
+#[derive(Args, Debug)]
+pub struct View {
+    /// NDJSON files to read trees from. Reads from stdin if none are given.
+    #[arg(value_name = "FILE")]
+    files: Vec<PathBuf>,
+
+    #[command(flatten)]
+    filter: Filter,
+}
+
+impl View {
+    pub fn run(self) -> io::Result<()> {
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+
+        for tree in input::read_trees(&self.files) {
+            let tree = match self.filter.apply(tree?) {
+                Some(tree) => tree,
+                None => continue,
+            };
+
+            match print(&mut stdout, &tree) {
+                Ok(()) => {}
+                // Stop quietly when piped into `head` or `less`.
+                Err(err) if err.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Pretty-prints a tree and flushes the output.
+pub fn print(out: &mut impl Write, tree: &Tree) -> io::Result<()> {
+    let string = Pretty.fmt(tree).map_err(io::Error::other)?;
+    out.write_all(string.as_bytes())?;
+    out.flush()
+}
@@ -0,0 +1,130 @@
+//! Tests for receiving forwarded trees with `forest collect`.
+use std::fs;
+use std::io::Read;
+use std::net::{TcpListener, TcpStream};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing_forest::processor::forward::Forward;
+use tracing_forest::util::*;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::Registry;
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Retries `f` until it returns `Some`, or panics after a few seconds.
+fn wait_for<T>(mut f: impl FnMut() -> Option<T>) -> T {
+    let start = Instant::now();
+    loop {
+        if let Some(value) = f() {
+            return value;
+        }
+        assert!(start.elapsed() < Duration::from_secs(10), "timed out");
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[test]
+fn test_collect_from_services() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let json = dir.path().join("trees.ndjson");
+    let address = TcpListener::bind("127.0.0.1:0")?.local_addr()?.to_string();
+
+    let mut collector = Command::new(env!("CARGO_BIN_EXE_forest"))
+        .args(["collect", "--tcp", &address, "--json"])
+        .arg(&json)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    wait_for(|| TcpStream::connect(&address).ok());
+
+    for service in ["checkout", "inventory"] {
+        let forward = Forward::tcp(address.clone()).service(service);
+        let subscriber = Registry::default().with(ForestLayer::from(forward));
+        tracing::subscriber::with_default(subscriber, || {
+            info_span!("request").in_scope(|| {
+                info!(service, "handled");
+            });
+        });
+    }
+
+    let lines = wait_for(|| {
+        let contents = fs::read_to_string(&json).ok()?;
+        let lines: Vec<String> = contents.lines().map(String::from).collect();
+        (lines.len() == 2).then_some(lines)
+    });
+
+    collector.kill()?;
+    let mut stdout = String::new();
+    collector
+        .stdout
+        .take()
+        .unwrap()
+        .read_to_string(&mut stdout)?;
+    collector.wait()?;
+
+    let mut services = Vec::new();
+    for line in lines {
+        let record: serde_json::Value = serde_json::from_str(&line)?;
+        assert!(record["tree"]["Span"]["name"] == "request");
+        services.push(record["service"].as_str().unwrap().to_string());
+    }
+    services.sort();
+    assert!(services == ["checkout", "inventory"]);
+
+    // The collected file can be read by the other commands.
+    let view = Command::new(env!("CARGO_BIN_EXE_forest"))
+        .arg("view")
+        .arg(&json)
+        .output()?;
+    assert!(view.status.success());
+    assert!(String::from_utf8(view.stdout)?.matches("request").count() == 2);
+
+    assert!(stdout.contains("[checkout] "));
+    assert!(stdout.contains("[inventory] "));
+    assert!(stdout
+        .lines()
+        .all(|line| line.starts_with("[checkout] ") || line.starts_with("[inventory] ")));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_collect_replaces_stale_socket() -> Result<()> {
+    use std::os::unix::net::UnixListener;
+
+    let dir = tempfile::tempdir()?;
+    let socket = dir.path().join("forest.sock");
+    let json = dir.path().join("trees.ndjson");
+
+    // A collector that didn't shut down cleanly leaves its socket file behind.
+    drop(UnixListener::bind(&socket)?);
+    assert!(socket.exists());
+
+    let mut collector = Command::new(env!("CARGO_BIN_EXE_forest"))
+        .args(["collect", "--quiet", "--unix"])
+        .arg(&socket)
+        .arg("--json")
+        .arg(&json)
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    let forward = Forward::unix(&socket).service("checkout");
+    let subscriber = Registry::default().with(ForestLayer::from(forward));
+    tracing::subscriber::with_default(subscriber, || {
+        wait_for(|| std::os::unix::net::UnixStream::connect(&socket).ok());
+        info!("hello");
+    });
+
+    let line = wait_for(|| fs::read_to_string(&json).ok().filter(|s| !s.is_empty()));
+    collector.kill()?;
+    collector.wait()?;
+
+    let record: serde_json::Value = serde_json::from_str(line.trim())?;
+    assert!(record["service"] == "checkout");
+    assert!(record["tree"]["Event"]["message"] == "hello");
+
+    Ok(())
+}
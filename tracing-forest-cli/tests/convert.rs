@@ -0,0 +1,82 @@
+//! Tests for converting `tracing_subscriber::fmt` JSON logs with `forest convert`.
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+const LOGS: &str = r#"{"timestamp":"2022-03-24T16:00:00.000000Z","level":"INFO","fields":{"message":"starting"},"target":"app"}
+{"timestamp":"2022-03-24T16:00:00.001000Z","level":"INFO","fields":{"message":"received","user":"alice"},"target":"app","span":{"id":1,"name":"request"},"spans":[{"id":1,"name":"request"}]}
+garbage
+{"timestamp":"2022-03-24T16:00:00.002000Z","level":"WARN","fields":{"message":"slow"},"target":"app","span":{"name":"render"},"spans":[{"id":1,"name":"request"},{"name":"render"}]}
+{"timestamp":"2022-03-24T16:00:00.003000Z","level":"WARN","fields":{"message":"close","time.busy":"1.00ms","time.idle":"5.00µs"},"target":"app","span":{"name":"render"},"spans":[{"id":1,"name":"request"},{"name":"render"}]}
+{"timestamp":"2022-03-24T16:00:00.004000Z","level":"INFO","fields":{"message":"close","time.busy":"3.00ms","time.idle":"5.00µs"},"target":"app","span":{"id":1,"name":"request"},"spans":[{"id":1,"name":"request"}]}
+"#;
+
+fn convert(args: &[&str]) -> Result<String> {
+    convert_input(LOGS.as_bytes(), args)
+}
+
+fn convert_input(input: &[u8], args: &[&str]) -> Result<String> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_forest"))
+        .arg("convert")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    child.stdin.take().unwrap().write_all(input)?;
+    let output = child.wait_with_output()?;
+    assert!(output.status.success());
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[test]
+fn test_convert() -> Result<()> {
+    let out = convert(&[])?;
+    let lines: Vec<serde_json::Value> = out
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<core::result::Result<_, _>>()?;
+
+    assert!(lines.len() == 2);
+    assert!(lines[0]["Event"]["message"] == "starting");
+
+    let request = &lines[1]["Span"];
+    assert!(request["name"] == "request");
+    assert!(request["fields"]["id"] == "1");
+    assert!(request["nanos_total"] == 3_000_000);
+    assert!(request["nanos_nested"] == 1_000_000);
+    assert!(request["nodes"][0]["Event"]["fields"]["user"] == "alice");
+    assert!(request["nodes"][1]["Span"]["level"] == "WARN");
+    assert!(request["nodes"][1]["Span"]["nodes"][0]["Event"]["message"] == "slow");
+
+    Ok(())
+}
+
+#[test]
+fn test_convert_pretty() -> Result<()> {
+    let out = convert(&["--pretty", "--span", "request"])?;
+    assert!(!out.contains("starting"));
+    assert!(out.contains("request [ 3.00ms"));
+    assert!(out.contains("render [ 1.00ms"));
+    assert!(out.contains("slow"));
+    Ok(())
+}
+
+#[test]
+fn test_convert_invalid_utf8() -> Result<()> {
+    let mut input = b"{\"timestamp\":\"2022-03-24T16:00:00.000000Z\",\"level\":\"INFO\",\"fields\":{\"message\":\"caf\xe9\"},\"target\":\"app\"}\n".to_vec();
+    input.extend_from_slice(LOGS.as_bytes());
+
+    let out = convert_input(&input, &[])?;
+    let lines: Vec<serde_json::Value> = out
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<core::result::Result<_, _>>()?;
+
+    assert!(lines.len() == 3);
+    assert!(lines[0]["Event"]["message"] == "caf\u{fffd}");
+    assert!(lines[1]["Event"]["message"] == "starting");
+    Ok(())
+}
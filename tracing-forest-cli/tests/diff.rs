@@ -0,0 +1,63 @@
+//! Tests for comparing recorded runs with `forest diff`.
+use std::fs;
+use std::process::Command;
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+fn span(name: &str, micros: u64, children: &[String]) -> String {
+    format!(
+        r#"{{"Span":{{"level":"INFO","fields":{{}},"name":"{}","nanos_total":{},"nanos_nested":0,"nodes":[{}]}}}}"#,
+        name,
+        micros * 1000,
+        children.join(",")
+    )
+}
+
+fn run(query: u64, render: u64, extra: &str) -> String {
+    let children = [
+        span("query", query, &[]),
+        span("render", render, &[]),
+        span(extra, 1, &[]),
+    ];
+    let line = span("request", query + render + 1, &children);
+    format!("{}\n{}\n", line, line)
+}
+
+#[test]
+fn test_diff() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let before = dir.path().join("before.ndjson");
+    let after = dir.path().join("after.ndjson");
+    fs::write(&before, run(100, 200, "cache"))?;
+    fs::write(&after, run(200, 100, "retry"))?;
+
+    let output = Command::new(env!("CARGO_BIN_EXE_forest"))
+        .arg("diff")
+        .args([&before, &after])
+        .output()?;
+    assert!(output.status.success());
+    let out = String::from_utf8(output.stdout)?;
+
+    let regressions = out.find("Regressions:").unwrap();
+    let improvements = out.find("Improvements:").unwrap();
+    let query = out.find("  request/query").unwrap();
+    let render = out.find("  request/render").unwrap();
+    assert!(regressions < query && query < improvements && improvements < render);
+    assert!(out.contains("100µs -> 200µs      +100.0%"));
+    assert!(out.contains("200µs -> 100µs      -50.0%"));
+    assert!(out.contains("1 paths changed by less than 5%"));
+    assert!(out.contains("  request/cache  (count 2, mean 1.00µs)"));
+    assert!(out.contains("  request/retry  (count 2, mean 1.00µs)"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_forest"))
+        .arg("diff")
+        .args([&before, &after])
+        .args(["--all", "--threshold", "60"])
+        .output()?;
+    let out = String::from_utf8(output.stdout)?;
+    assert!(out.contains("Regressions:\n  request/query"));
+    assert!(!out.contains("Improvements:"));
+    assert!(out.contains("Unchanged:\n  request\n"));
+
+    Ok(())
+}
@@ -0,0 +1,149 @@
+//! Tests for following a growing NDJSON file with `forest follow`.
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+fn event(level: &str, message: &str) -> String {
+    format!(
+        r#"{{"Event":{{"uuid":"00000000-0000-0000-0000-000000000000","timestamp":"2022-03-24T16:00:00+00:00","level":"{}","fields":{{}},"message":"{}","tag":null}}}}"#,
+        level, message
+    )
+}
+
+fn append(path: &Path, text: &str) -> Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(text.as_bytes())?;
+    Ok(())
+}
+
+struct Follow {
+    child: Child,
+    lines: Receiver<String>,
+}
+
+impl Follow {
+    fn spawn(args: &[&str]) -> Result<Self> {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_forest"))
+            .args(["follow", "--interval", "10"])
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let stdout = child.stdout.take().unwrap();
+        let (tx, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                if tx.send(line.unwrap()).is_err() {
+                    return;
+                }
+            }
+        });
+
+        // Give the follower time to open the file and seek to the end.
+        thread::sleep(Duration::from_millis(200));
+        Ok(Follow { child, lines })
+    }
+
+    /// Waits for a line containing `text`, returning the lines before it.
+    fn expect(&self, text: &str) -> Vec<String> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut skipped = Vec::new();
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            match self.lines.recv_timeout(timeout) {
+                Ok(line) if line.contains(text) => return skipped,
+                Ok(line) => skipped.push(line),
+                Err(_) => panic!("timed out waiting for {:?}, got {:?}", text, skipped),
+            }
+        }
+    }
+}
+
+impl Drop for Follow {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn test_follow_new_lines() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("trees.log");
+    append(&path, &format!("{}\n", event("INFO", "old")))?;
+    // A partially written line when the follower starts is skipped.
+    append(&path, &event("INFO", "also old")[..20])?;
+
+    let follow = Follow::spawn(&[path.to_str().unwrap()])?;
+
+    append(&path, "rest of the old line\n")?;
+    append(&path, &format!("{}\n", event("INFO", "first")))?;
+    assert!(follow.expect("first").is_empty());
+
+    // Trees are only shown once their line is complete.
+    let line = event("INFO", "second");
+    let (start, end) = line.split_at(40);
+    append(&path, start)?;
+    thread::sleep(Duration::from_millis(100));
+    append(&path, &format!("{}\n", end))?;
+    assert!(follow.expect("second").is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_follow_filters_and_rotation() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("trees.log");
+    append(&path, "")?;
+
+    let follow = Follow::spawn(&[path.to_str().unwrap(), "--level", "warn"])?;
+
+    append(&path, &format!("{}\n", event("INFO", "quiet")))?;
+    append(&path, &format!("{}\n", event("WARN", "before rotation")))?;
+    assert!(follow.expect("before rotation").is_empty());
+
+    // Lines written just before rotation are still read from the old file.
+    append(&path, &format!("{}\n", event("WARN", "last words")))?;
+    fs::rename(&path, dir.path().join("trees.log.1"))?;
+    append(&path, &format!("{}\n", event("ERROR", "after rotation")))?;
+    assert!(follow.expect("last words").is_empty());
+    assert!(follow.expect("after rotation").is_empty());
+
+    fs::write(&path, "")?;
+    thread::sleep(Duration::from_millis(100));
+    append(&path, &format!("{}\n", event("ERROR", "after truncation")))?;
+    assert!(follow.expect("after truncation").is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_follow_directory() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    append(&dir.path().join("forest.0.log"), "")?;
+
+    let follow = Follow::spawn(&[dir.path().to_str().unwrap()])?;
+
+    append(
+        &dir.path().join("forest.0.log"),
+        &format!("{}\n", event("INFO", "zero")),
+    )?;
+    assert!(follow.expect("zero").is_empty());
+
+    thread::sleep(Duration::from_millis(50));
+    append(
+        &dir.path().join("forest.1.log"),
+        &format!("{}\n", event("INFO", "one")),
+    )?;
+    assert!(follow.expect("one").is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,163 @@
+//! Tests for `forest tui` outside of a terminal, and for the browser's state.
+use std::process::{Command, Stdio};
+use tracing_forest::tree::Tree;
+
+// The browser's state doesn't depend on the terminal, so it's tested directly.
+#[path = "../src/tui/app.rs"]
+#[allow(dead_code)]
+mod app;
+
+use app::{App, Entry};
+
+const TREES: &[&str] = &[
+    r#"{"Span":{"level":"INFO","fields":{},"name":"request","nanos_total":10000,"nanos_nested":6000,"nodes":[{"Event":{"level":"INFO","fields":{},"message":"started","tag":null}},{"Span":{"level":"INFO","fields":{},"name":"fast","nanos_total":1000,"nanos_nested":0,"nodes":[{"Event":{"level":"INFO","fields":{},"message":"fast done","tag":null}}]}},{"Span":{"level":"INFO","fields":{"table":"\"users\""},"name":"slow","nanos_total":5000,"nanos_nested":0,"nodes":[{"Event":{"level":"ERROR","fields":{},"message":"timed out","tag":null}}]}}]}}"#,
+    r#"{"Event":{"level":"WARN","fields":{},"message":"low disk","tag":null}}"#,
+];
+
+fn app() -> App {
+    let mut app = App::default();
+    app.extend(TREES.iter().map(|json| Entry {
+        service: None,
+        tree: serde_json::from_str::<Tree>(json).unwrap(),
+    }));
+    app
+}
+
+fn rows(app: &App) -> Vec<Vec<usize>> {
+    app.rows.iter().map(|row| row.path.clone()).collect()
+}
+
+fn selected(app: &App) -> Vec<usize> {
+    app.rows[app.cursor].path.clone()
+}
+
+#[test]
+fn test_requires_terminal() -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::new(env!("CARGO_BIN_EXE_forest"))
+        .arg("tui")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()?;
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("must be run in a terminal"));
+    Ok(())
+}
+
+#[test]
+fn test_collapse_and_expand() {
+    let mut app = app();
+    assert!(rows(&app) == [vec![0], vec![1]]);
+
+    app.toggle();
+    assert!(rows(&app) == [vec![0], vec![0, 0], vec![0, 1], vec![0, 2], vec![1]]);
+
+    app.move_by(2);
+    app.expand();
+    assert!(selected(&app) == [0, 1]);
+    assert!(rows(&app).contains(&vec![0, 1, 0]));
+
+    app.collapse();
+    assert!(selected(&app) == [0, 1]);
+    assert!(!rows(&app).contains(&vec![0, 1, 0]));
+
+    // Collapsing a collapsed span moves to its parent.
+    app.collapse();
+    assert!(selected(&app) == [0]);
+
+    app.move_by(100);
+    assert!(selected(&app) == [1]);
+}
+
+#[test]
+fn test_expand_all() {
+    let mut app = app();
+    app.expand_all();
+    assert!(
+        rows(&app)
+            == [
+                vec![0],
+                vec![0, 0],
+                vec![0, 1],
+                vec![0, 1, 0],
+                vec![0, 2],
+                vec![0, 2, 0],
+                vec![1],
+            ]
+    );
+
+    app.move_by(3);
+    app.collapse_all();
+    assert!(rows(&app) == [vec![0], vec![1]]);
+    assert!(selected(&app) == [0]);
+}
+
+#[test]
+fn test_next_error() {
+    let mut app = app();
+    app.next_error(true);
+    assert!(selected(&app) == [0, 2, 0]);
+    assert!(
+        rows(&app)
+            == [
+                vec![0],
+                vec![0, 0],
+                vec![0, 1],
+                vec![0, 2],
+                vec![0, 2, 0],
+                vec![1]
+            ]
+    );
+    assert!(app.status.is_none());
+
+    // The only error is found again after wrapping around.
+    app.next_error(false);
+    assert!(selected(&app) == [0, 2, 0]);
+
+    let mut app = App::default();
+    app.extend(Some(Entry {
+        service: None,
+        tree: serde_json::from_str::<Tree>(TREES[1]).unwrap(),
+    }));
+    app.next_error(true);
+    assert!(app.status.as_deref() == Some("No errors"));
+}
+
+#[test]
+fn test_search() {
+    let mut app = app();
+    app.input = Some("DISK".to_string());
+    app.submit_search();
+    assert!(app.search.as_deref() == Some("disk"));
+    assert!(selected(&app) == [1]);
+    assert!(app.is_match(&[1]));
+    assert!(!app.is_match(&[0]));
+
+    // Field values are searched too.
+    app.input = Some("users".to_string());
+    app.submit_search();
+    assert!(selected(&app) == [0, 2]);
+
+    app.input = Some("missing".to_string());
+    app.submit_search();
+    assert!(selected(&app) == [0, 2]);
+    assert!(app.status.as_deref() == Some("No matches for \"missing\""));
+}
+
+#[test]
+fn test_sort_by_duration() {
+    let mut app = app();
+    app.toggle();
+    app.move_by(1);
+    assert!(selected(&app) == [0, 0]);
+
+    app.toggle_sort();
+    assert!(rows(&app) == [vec![0], vec![0, 2], vec![0, 1], vec![0, 0], vec![1]]);
+    assert!(selected(&app) == [0, 0]);
+
+    app.toggle_sort();
+    assert!(rows(&app) == [vec![0], vec![0, 0], vec![0, 1], vec![0, 2], vec![1]]);
+}
@@ -0,0 +1,72 @@
+//! Tests for pretty-printing NDJSON trees with `forest view`.
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+const TREES: &str = r#"{"Span":{"uuid":"11111111-1111-1111-1111-111111111111","timestamp":"2022-03-24T16:00:00+00:00","level":"INFO","fields":{"user":"\"alice\""},"name":"checkout","nanos_total":2000,"nanos_nested":0,"nodes":[{"Event":{"uuid":"11111111-1111-1111-1111-111111111111","timestamp":"2022-03-24T16:00:00.5+00:00","level":"DEBUG","fields":{},"message":"checkout debug","tag":null}},{"Event":{"uuid":"11111111-1111-1111-1111-111111111111","timestamp":"2022-03-24T16:00:01+00:00","level":"ERROR","fields":{},"message":"card declined","tag":"payment.error"}}]}}
+
+not a tree
+{"Span":{"uuid":"22222222-2222-2222-2222-222222222222","timestamp":"2022-03-24T17:00:00+00:00","level":"INFO","fields":{"user":"\"bob\""},"name":"search","nanos_total":1000,"nanos_nested":0,"nodes":[{"Event":{"uuid":"22222222-2222-2222-2222-222222222222","timestamp":"2022-03-24T17:00:00.5+00:00","level":"INFO","fields":{"query":"\"shoes\""},"message":"searching","tag":null}}]}}
+{"Event":{"uuid":"33333333-3333-3333-3333-333333333333","timestamp":"2022-03-24T18:00:00+00:00","level":"WARN","fields":{},"message":"low disk","tag":null}}
+"#;
+
+fn view(args: &[&str]) -> Result<String> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_forest"))
+        .arg("view")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    child.stdin.take().unwrap().write_all(TREES.as_bytes())?;
+    let output = child.wait_with_output()?;
+    assert!(output.status.success());
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[test]
+fn test_view_all() -> Result<()> {
+    let out = view(&[])?;
+    assert!(out.contains("checkout"));
+    assert!(out.contains("checkout debug"));
+    assert!(out.contains("search"));
+    assert!(out.contains("low disk"));
+    Ok(())
+}
+
+#[test]
+fn test_filters() -> Result<()> {
+    let out = view(&["--level", "info"])?;
+    assert!(!out.contains("checkout debug"));
+    assert!(out.contains("card declined"));
+
+    let out = view(&["--span", "search"])?;
+    assert!(out.contains("searching") && !out.contains("checkout") && !out.contains("low disk"));
+
+    let out = view(&["--uuid", "11111111-1111-1111-1111-111111111111"])?;
+    assert!(out.contains("checkout") && !out.contains("search"));
+
+    let out = view(&["--uuid", "33333333-3333-3333-3333-333333333333"])?;
+    assert!(out.contains("low disk") && !out.contains("checkout"));
+
+    let out = view(&["--tag", "payment"])?;
+    assert!(out.contains("card declined") && !out.contains("search"));
+
+    let out = view(&[
+        "--since",
+        "2022-03-24T16:30:00Z",
+        "--until",
+        "2022-03-24T17:30:00Z",
+    ])?;
+    assert!(out.contains("searching") && !out.contains("checkout") && !out.contains("low disk"));
+
+    let out = view(&["--field", "user=alice"])?;
+    assert!(out.contains("checkout") && !out.contains("search"));
+
+    let out = view(&["--field", "query=shoes", "--field", "user=alice"])?;
+    assert!(out.is_empty());
+
+    Ok(())
+}
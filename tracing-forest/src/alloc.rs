@@ -0,0 +1,171 @@
+//! Attribute heap allocations to the spans they happen in.
+//!
+//! Installing [`CountingAlloc`] as the global allocator counts the allocations
+//! made by each thread. A [`ForestLayer`] then records how many allocations,
+//! and how many bytes, each span's thread made while the span was entered, so
+//! the spans that allocate the most can be found without a separate heap
+//! profiler.
+//!
+//! Counts are recorded both including and excluding child spans, like span
+//! durations. See [`Span::alloc_count`] and [`Span::self_alloc_count`] for
+//! details.
+//!
+//! Allocations made by the layer itself, like when recording fields, aren't
+//! counted, so they don't inflate the counts of the spans they happen in.
+//!
+//! If `CountingAlloc` isn't the global allocator, all counts are zero.
+//!
+//! [`ForestLayer`]: crate::ForestLayer
+//! [`Span::alloc_count`]: crate::tree::Span::alloc_count
+//! [`Span::self_alloc_count`]: crate::tree::Span::self_alloc_count
+//!
+//! # Examples
+//!
+//! ```
+//! use tracing_forest::alloc::CountingAlloc;
+//! use tracing_forest::util::*;
+//!
+//! #[global_allocator]
+//! static ALLOC: CountingAlloc = CountingAlloc::system();
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let logs = tracing_forest::capture()
+//!         .build()
+//!         .on(async {
+//!             info_span!("parse").in_scope(|| {
+//!                 let buffer = vec![0u8; 4096];
+//!                 drop(buffer);
+//!             });
+//!         })
+//!         .await;
+//!
+//!     let parse = logs[0].span().unwrap();
+//!     assert!(parse.alloc_count() >= 1);
+//!     assert!(parse.alloc_bytes() >= 4096);
+//! }
+//! ```
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+thread_local! {
+    /// The number of allocations and bytes allocated by the current thread.
+    static ALLOCATED: Cell<Allocs> = const { Cell::new(Allocs::ZERO) };
+
+    /// Whether counting is paused on the current thread.
+    static PAUSED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// A global allocator that counts the allocations made by each thread.
+///
+/// Allocation is delegated to another allocator, which is [`System`] by
+/// default. Reallocations are counted as an allocation of the new size, and
+/// deallocations aren't counted.
+///
+/// See the [module-level documentation](self) for more details.
+#[derive(Debug, Default)]
+pub struct CountingAlloc<A = System> {
+    inner: A,
+}
+
+impl CountingAlloc<System> {
+    /// Returns a `CountingAlloc` that delegates to the [`System`] allocator.
+    pub const fn system() -> Self {
+        CountingAlloc { inner: System }
+    }
+}
+
+impl<A> CountingAlloc<A> {
+    /// Returns a `CountingAlloc` that delegates to another allocator.
+    pub const fn new(inner: A) -> Self {
+        CountingAlloc { inner }
+    }
+}
+
+// SAFETY: Every method delegates to the inner allocator, and counting doesn't
+// allocate.
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAlloc<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        self.inner.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.inner.dealloc(ptr, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        self.inner.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count(new_size);
+        self.inner.realloc(ptr, layout, new_size)
+    }
+}
+
+fn count(bytes: usize) {
+    if PAUSED.try_with(Cell::get).unwrap_or(false) {
+        return;
+    }
+
+    // The counter can't be accessed while the thread is being torn down, so
+    // those allocations aren't counted.
+    let _ = ALLOCATED.try_with(|allocated| {
+        let Allocs { count, bytes: total } = allocated.get();
+        allocated.set(Allocs {
+            count: count.wrapping_add(1),
+            bytes: total.wrapping_add(bytes as u64),
+        });
+    });
+}
+
+/// Stops counting the current thread's allocations until the returned guard
+/// is dropped.
+pub(crate) fn pause() -> Paused {
+    let was_paused = PAUSED.try_with(|paused| paused.replace(true)).unwrap_or(false);
+    Paused { was_paused }
+}
+
+/// A guard that resumes counting allocations when dropped, unless counting
+/// was already paused when it was created.
+pub(crate) struct Paused {
+    was_paused: bool,
+}
+
+impl Drop for Paused {
+    fn drop(&mut self) {
+        let _ = PAUSED.try_with(|paused| paused.set(self.was_paused));
+    }
+}
+
+/// A number of allocations and the bytes they allocated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Allocs {
+    pub(crate) count: u64,
+    pub(crate) bytes: u64,
+}
+
+impl Allocs {
+    const ZERO: Allocs = Allocs { count: 0, bytes: 0 };
+
+    /// Returns the allocations made by the current thread so far.
+    pub(crate) fn current() -> Allocs {
+        ALLOCATED
+            .try_with(Cell::get)
+            .unwrap_or(Allocs::ZERO)
+    }
+
+    pub(crate) fn saturating_sub(self, other: Allocs) -> Allocs {
+        Allocs {
+            count: self.count.saturating_sub(other.count),
+            bytes: self.bytes.saturating_sub(other.bytes),
+        }
+    }
+
+    pub(crate) fn add(&mut self, other: Allocs) {
+        self.count = self.count.saturating_add(other.count);
+        self.bytes = self.bytes.saturating_add(other.bytes);
+    }
+}
@@ -45,3 +45,87 @@ macro_rules! cfg_chrono {
         )*
     }
 }
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_rolling_file {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "rolling-file")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "rolling-file")))]
+            $item
+        )*
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_forward {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "forward")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "forward")))]
+            $item
+        )*
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_fmt_json {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "fmt-json")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "fmt-json")))]
+            $item
+        )*
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_sqlite {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "sqlite")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "sqlite")))]
+            $item
+        )*
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_regex {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "regex")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "regex")))]
+            $item
+        )*
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_alloc_count {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "alloc-count")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "alloc-count")))]
+            $item
+        )*
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! cfg_cpu_time {
+    ($($item:item)*) => {
+        $(
+            #[cfg(all(feature = "cpu-time", target_os = "linux"))]
+            #[cfg_attr(docsrs, doc(cfg(all(feature = "cpu-time", target_os = "linux"))))]
+            $item
+        )*
+    }
+}
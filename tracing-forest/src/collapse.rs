@@ -0,0 +1,284 @@
+//! Merge repeated events and spans to keep noisy trees readable.
+//!
+//! See [`Collapse`] for more details.
+use crate::printer::{DurationDisplay, Formatter};
+use crate::processor::{self, Processor};
+use crate::tree::{self, Event, Span, Tree};
+use std::time::Duration;
+
+/// A transform that merges repeated nodes in a [`Tree`].
+///
+/// Within each span:
+/// * Consecutive sibling spans with the same name are merged into one
+///   aggregate span in place of the first, named like `attempt ×1000`. Its
+///   durations are the sums of the merged spans' durations, and it has
+///   `collapse.min` and `collapse.max` fields with the shortest and longest
+///   total durations. It keeps the fields that all of the merged spans have in
+///   common, and its children are the children of every merged span,
+///   collapsed in turn.
+/// * Consecutive events with the same level, tag, message, and fields are
+///   merged into the first, with the message followed by a count like
+///   `retrying ×500`. With the `chrono` feature, it has `collapse.first` and
+///   `collapse.last` fields with the timestamps of the first and last merged
+///   events.
+///
+/// The added fields replace any fields with the same keys.
+///
+/// Only consecutive nodes are merged, so the order of what happened is kept.
+/// Spans are merged before their children are collapsed, so the events of
+/// repeated spans, like the attempts of a retry loop, are merged as well.
+///
+/// A `Collapse` can be applied directly to a tree with [`apply`], or wrapped
+/// around a [`Processor`] or [`Formatter`] with [`wrap`].
+///
+/// [`apply`]: Collapse::apply
+/// [`wrap`]: Collapse::wrap
+///
+/// # Examples
+///
+/// Pretty-printing collapsed trees, which can also be written as
+/// [`Pretty.collapsed()`](crate::printer::Pretty::collapsed).
+/// ```
+/// use tracing_forest::collapse::Collapse;
+/// use tracing_forest::printer::{Pretty, Printer};
+///
+/// let printer = Printer::new().formatter(Collapse::new().wrap(Pretty));
+/// # let _ = printer;
+/// ```
+///
+/// Collapsing trees before they reach a processor.
+/// ```
+/// use tracing_forest::collapse::Collapse;
+/// use tracing_forest::{ForestLayer, PrettyPrinter};
+///
+/// let layer = ForestLayer::from(Collapse::new().spans(false).wrap(PrettyPrinter::new()));
+/// # let _ = layer;
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Collapse {
+    events: bool,
+    spans: bool,
+}
+
+impl Collapse {
+    /// Returns a new `Collapse` that merges both events and spans.
+    pub const fn new() -> Self {
+        Collapse {
+            events: true,
+            spans: true,
+        }
+    }
+
+    /// Set whether consecutive identical events are merged. Defaults to `true`.
+    pub const fn events(mut self, events: bool) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Set whether consecutive sibling spans with the same name are merged.
+    /// Defaults to `true`.
+    pub const fn spans(mut self, spans: bool) -> Self {
+        self.spans = spans;
+        self
+    }
+
+    /// Returns a [`Processor`] or [`Formatter`] that collapses trees before
+    /// passing them to `inner`.
+    pub const fn wrap<T>(self, inner: T) -> Collapsed<T> {
+        Collapsed {
+            collapse: self,
+            inner,
+        }
+    }
+
+    /// Merges the repeated nodes of a tree.
+    pub fn apply(&self, tree: Tree) -> Tree {
+        match tree {
+            Tree::Span(mut span) => {
+                self.collapse_span(&mut span);
+                Tree::Span(span)
+            }
+            event => event,
+        }
+    }
+
+    fn collapse_span(&self, span: &mut Span) {
+        let mut nodes = std::mem::take(&mut span.nodes);
+
+        if self.spans {
+            nodes = merge_spans(nodes);
+        }
+
+        for node in nodes.iter_mut() {
+            if let Tree::Span(span) = node {
+                self.collapse_span(span);
+            }
+        }
+
+        if self.events {
+            nodes = merge_events(nodes);
+        }
+
+        span.nodes = nodes;
+    }
+}
+
+impl Default for Collapse {
+    fn default() -> Self {
+        Collapse::new()
+    }
+}
+
+/// A [`Processor`] or [`Formatter`] that collapses trees first.
+///
+/// This type is returned by [`Collapse::wrap`].
+#[derive(Clone, Debug)]
+pub struct Collapsed<T> {
+    collapse: Collapse,
+    inner: T,
+}
+
+impl<P: Processor> Processor for Collapsed<P> {
+    fn process(&self, tree: Tree) -> processor::Result {
+        self.inner.process(self.collapse.apply(tree))
+    }
+}
+
+impl<F: Formatter> Formatter for Collapsed<F> {
+    type Error = F::Error;
+
+    fn fmt(&self, tree: &Tree) -> Result<String, F::Error> {
+        self.inner.fmt(&self.collapse.apply(tree.clone()))
+    }
+}
+
+/// Merges runs of sibling spans with the same name into the first of each run.
+fn merge_spans(nodes: Vec<Tree>) -> Vec<Tree> {
+    // Each merged node, with how many spans it includes and the shortest and
+    // longest of them.
+    let mut merged: Vec<(Tree, usize, Duration, Duration)> = Vec::with_capacity(nodes.len());
+
+    for node in nodes {
+        let span = match node {
+            Tree::Span(span) => span,
+            event => {
+                merged.push((event, 1, Duration::ZERO, Duration::ZERO));
+                continue;
+            }
+        };
+
+        match merged.last_mut() {
+            Some((Tree::Span(first), count, min, max)) if first.name == span.name => {
+                *count += 1;
+                *min = (*min).min(span.total_duration);
+                *max = (*max).max(span.total_duration);
+                first.total_duration += span.total_duration;
+                first.inner_duration += span.inner_duration;
+                first.lifetime += span.lifetime;
+                first.entries += span.entries;
+                #[cfg(all(feature = "cpu-time", target_os = "linux"))]
+                {
+                    first.cpu_time += span.cpu_time;
+                }
+                #[cfg(feature = "alloc-count")]
+                {
+                    first.alloc_count += span.alloc_count;
+                    first.alloc_bytes += span.alloc_bytes;
+                    first.self_alloc_count += span.self_alloc_count;
+                    first.self_alloc_bytes += span.self_alloc_bytes;
+                }
+                first.max_entry_duration = first.max_entry_duration.max(span.max_entry_duration);
+                #[cfg(feature = "chrono")]
+                {
+                    first.closed_at = first.closed_at.max(span.closed_at);
+                }
+                first
+                    .shared
+                    .fields
+                    .retain(|field| span.shared.fields.contains(field));
+                #[cfg(feature = "uuid")]
+                for id in span.follows_from {
+                    if !first.follows_from.contains(&id) {
+                        first.follows_from.push(id);
+                    }
+                }
+                first.nodes.extend(span.nodes);
+            }
+            _ => {
+                let duration = span.total_duration;
+                merged.push((Tree::Span(span), 1, duration, duration));
+            }
+        }
+    }
+
+    merged
+        .into_iter()
+        .map(|(mut node, count, min, max)| {
+            if let Tree::Span(span) = &mut node {
+                if count > 1 {
+                    span.name = format!("{} ×{}", span.name, count).into();
+                    let fields = &mut span.shared.fields;
+                    set_field(fields, "collapse.min", DurationDisplay(min).to_string());
+                    set_field(fields, "collapse.max", DurationDisplay(max).to_string());
+                }
+            }
+            node
+        })
+        .collect()
+}
+
+/// Merges runs of identical events into the first of each run.
+fn merge_events(nodes: Vec<Tree>) -> Vec<Tree> {
+    // Each merged node, with how many events it includes and the last of them.
+    let mut merged: Vec<(Tree, usize, Option<Event>)> = Vec::with_capacity(nodes.len());
+
+    for node in nodes {
+        let repeated = match (&node, merged.last()) {
+            (Tree::Event(event), Some((Tree::Event(first), _, _))) => same_event(first, event),
+            _ => false,
+        };
+
+        match (node, merged.last_mut()) {
+            (Tree::Event(event), Some((_, count, last))) if repeated => {
+                *count += 1;
+                *last = Some(event);
+            }
+            (node, _) => merged.push((node, 1, None)),
+        }
+    }
+
+    merged
+        .into_iter()
+        .map(|(mut node, count, _last)| {
+            if let (Tree::Event(first), true) = (&mut node, count > 1) {
+                first.message = Some(match first.message.take() {
+                    Some(message) => format!("{} ×{}", message, count),
+                    None => format!("×{}", count),
+                });
+
+                #[cfg(feature = "chrono")]
+                if let Some(last) = _last {
+                    let first_at = first.shared.timestamp.to_rfc3339();
+                    let last_at = last.shared.timestamp.to_rfc3339();
+                    let fields = &mut first.shared.fields;
+                    set_field(fields, "collapse.first", first_at);
+                    set_field(fields, "collapse.last", last_at);
+                }
+            }
+            node
+        })
+        .collect()
+}
+
+/// Adds a field, replacing any field with the same key.
+fn set_field(fields: &mut tree::FieldSet, key: &'static str, value: String) {
+    fields.retain(|field| field.key() != key);
+    fields.push(tree::Field::new(key, value));
+}
+
+fn same_event(a: &Event, b: &Event) -> bool {
+    a.level() == b.level()
+        && a.tag == b.tag
+        && a.message == b.message
+        && a.shared.fields == b.shared.fields
+}
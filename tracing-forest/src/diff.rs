@@ -0,0 +1,282 @@
+//! Compare span durations between two recorded runs.
+//!
+//! A [`Profile`] aggregates the durations of every span in a set of trees by
+//! their _path_, which is the names of the span and its ancestors joined with
+//! `/`, like `request/db::query`. Comparing two profiles with
+//! [`Profile::compare`] produces a [`Diff`] describing how the duration
+//! statistics of each path changed, and which paths only appear in one run.
+//!
+//! # Examples
+//!
+//! Comparing the trees captured from two runs of the same code.
+//! ```
+//! use tracing_forest::diff::Profile;
+//! use tracing_forest::util::*;
+//!
+//! async fn run() {
+//!     info_span!("request").in_scope(|| {
+//!         info_span!("query").in_scope(|| {});
+//!     });
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let before = tracing_forest::capture().build().on(run()).await;
+//!     let after = tracing_forest::capture().build().on(run()).await;
+//!
+//!     let before: Profile = before.into_iter().collect();
+//!     let after: Profile = after.into_iter().collect();
+//!
+//!     let diff = before.compare(&after);
+//!     assert!(diff.changes.len() == 2);
+//!     assert!(diff.changes[1].path == "request/query");
+//!     assert!(diff.removed.is_empty() && diff.added.is_empty());
+//!
+//!     for change in diff.regressions(0.05) {
+//!         println!("{} is {:+.1}% slower", change.path, change.mean_change() * 100.0);
+//!     }
+//! }
+//! ```
+use crate::tree::{Span, Tree};
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+use std::time::Duration;
+
+/// The durations of spans in a set of trees, grouped by path.
+///
+/// See the [module-level documentation](self) for more details.
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    spans: BTreeMap<String, Vec<Duration>>,
+}
+
+/// Summary statistics of the durations of spans at a path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    /// The number of spans.
+    pub count: usize,
+    /// The mean duration.
+    pub mean: Duration,
+    /// The shortest duration.
+    pub min: Duration,
+    /// The median duration.
+    pub p50: Duration,
+    /// The 90th percentile duration.
+    pub p90: Duration,
+    /// The 99th percentile duration.
+    pub p99: Duration,
+    /// The longest duration.
+    pub max: Duration,
+}
+
+/// The differences between two [`Profile`]s.
+///
+/// All paths are sorted alphabetically, so parents come before their children.
+#[derive(Clone, Debug, Default)]
+pub struct Diff {
+    /// Paths appearing in both profiles.
+    pub changes: Vec<Change>,
+    /// Paths only appearing in the first profile.
+    pub removed: Vec<(String, Stats)>,
+    /// Paths only appearing in the second profile.
+    pub added: Vec<(String, Stats)>,
+}
+
+/// How the durations of spans at a path changed between two [`Profile`]s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    /// The path of the spans.
+    pub path: String,
+    /// Statistics from the first profile.
+    pub before: Stats,
+    /// Statistics from the second profile.
+    pub after: Stats,
+}
+
+impl Profile {
+    /// Create an empty `Profile`.
+    pub fn new() -> Self {
+        Profile::default()
+    }
+
+    /// Add the durations of all spans in a tree.
+    ///
+    /// Events are ignored.
+    pub fn add(&mut self, tree: &Tree) {
+        if let Tree::Span(span) = tree {
+            let mut path = String::new();
+            self.add_span(span, &mut path);
+        }
+    }
+
+    fn add_span(&mut self, span: &Span, path: &mut String) {
+        let len = path.len();
+        if !path.is_empty() {
+            path.push('/');
+        }
+        path.push_str(span.name());
+
+        match self.spans.get_mut(path.as_str()) {
+            Some(durations) => durations.push(span.total_duration()),
+            None => {
+                self.spans.insert(path.clone(), vec![span.total_duration()]);
+            }
+        }
+
+        for node in span.nodes() {
+            if let Tree::Span(child) = node {
+                self.add_span(child, path);
+            }
+        }
+
+        path.truncate(len);
+    }
+
+    /// Returns whether no spans have been added.
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Returns an iterator over each path and the statistics of its spans,
+    /// sorted by path.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Stats)> + '_ {
+        self.spans
+            .iter()
+            .map(|(path, durations)| (path.as_str(), Stats::new(durations)))
+    }
+
+    /// Returns the statistics of the spans at a path, if there are any.
+    pub fn stats(&self, path: &str) -> Option<Stats> {
+        self.spans.get(path).map(|durations| Stats::new(durations))
+    }
+
+    /// Compare this profile to a later one.
+    pub fn compare(&self, after: &Profile) -> Diff {
+        let mut diff = Diff::default();
+
+        for (path, before) in self.iter() {
+            match after.stats(path) {
+                Some(after) => diff.changes.push(Change {
+                    path: path.to_string(),
+                    before,
+                    after,
+                }),
+                None => diff.removed.push((path.to_string(), before)),
+            }
+        }
+
+        for (path, stats) in after.iter() {
+            if !self.spans.contains_key(path) {
+                diff.added.push((path.to_string(), stats));
+            }
+        }
+
+        diff
+    }
+}
+
+impl Extend<Tree> for Profile {
+    fn extend<I: IntoIterator<Item = Tree>>(&mut self, iter: I) {
+        for tree in iter {
+            self.add(&tree);
+        }
+    }
+}
+
+impl<'a> Extend<&'a Tree> for Profile {
+    fn extend<I: IntoIterator<Item = &'a Tree>>(&mut self, iter: I) {
+        for tree in iter {
+            self.add(tree);
+        }
+    }
+}
+
+impl FromIterator<Tree> for Profile {
+    fn from_iter<I: IntoIterator<Item = Tree>>(iter: I) -> Self {
+        let mut profile = Profile::new();
+        profile.extend(iter);
+        profile
+    }
+}
+
+impl<'a> FromIterator<&'a Tree> for Profile {
+    fn from_iter<I: IntoIterator<Item = &'a Tree>>(iter: I) -> Self {
+        let mut profile = Profile::new();
+        profile.extend(iter);
+        profile
+    }
+}
+
+impl Stats {
+    fn new(durations: &[Duration]) -> Self {
+        let mut sorted = durations.to_vec();
+        sorted.sort_unstable();
+
+        let count = sorted.len();
+        let total: u128 = sorted.iter().map(Duration::as_nanos).sum();
+
+        // Nearest-rank percentiles.
+        let percentile = |p: usize| sorted[(p * count).div_ceil(100).clamp(1, count) - 1];
+
+        Stats {
+            count,
+            mean: Duration::from_nanos((total / count as u128) as u64),
+            min: sorted[0],
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: sorted[count - 1],
+        }
+    }
+}
+
+impl Diff {
+    /// Returns the paths whose mean duration increased by more than
+    /// `threshold`, as a fraction of the earlier mean, sorted from the largest
+    /// increase.
+    pub fn regressions(&self, threshold: f64) -> Vec<&Change> {
+        let mut regressions: Vec<&Change> = self
+            .changes
+            .iter()
+            .filter(|change| change.mean_change() > threshold)
+            .collect();
+        regressions.sort_by(|a, b| b.mean_change().total_cmp(&a.mean_change()));
+        regressions
+    }
+
+    /// Returns the paths whose mean duration decreased by more than
+    /// `threshold`, as a fraction of the earlier mean, sorted from the largest
+    /// decrease.
+    pub fn improvements(&self, threshold: f64) -> Vec<&Change> {
+        let mut improvements: Vec<&Change> = self
+            .changes
+            .iter()
+            .filter(|change| change.mean_change() < -threshold)
+            .collect();
+        improvements.sort_by(|a, b| a.mean_change().total_cmp(&b.mean_change()));
+        improvements
+    }
+}
+
+impl Change {
+    /// Returns the relative change in mean duration, where `0.1` means 10% slower.
+    pub fn mean_change(&self) -> f64 {
+        self.relative_change(|stats| stats.mean)
+    }
+
+    /// Returns the relative change in a statistic, like `|stats| stats.p99`.
+    pub fn relative_change(&self, stat: impl Fn(&Stats) -> Duration) -> f64 {
+        let before = stat(&self.before).as_secs_f64();
+        let after = stat(&self.after).as_secs_f64();
+
+        if before == 0.0 {
+            if after == 0.0 {
+                0.0
+            } else {
+                f64::INFINITY
+            }
+        } else {
+            after / before - 1.0
+        }
+    }
+}
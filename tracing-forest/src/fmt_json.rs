@@ -0,0 +1,393 @@
+//! Reconstruct log trees from the JSON output of `tracing_subscriber::fmt`.
+//!
+//! Services logging with `tracing_subscriber::fmt().json()` write one line per
+//! event, where each line describes the event's current span and, with
+//! [`with_span_list`], its ancestors. The [`Converter`] in this module uses
+//! these span lists to regroup events into [`Tree`]s, so that they can be
+//! viewed and analyzed with the same tools as trees from a [`ForestLayer`].
+//!
+//! # Span boundaries
+//!
+//! `fmt` doesn't include span IDs in its output, so spans are identified by
+//! their name and fields at each position in the span list. A span is opened
+//! by the first line containing it, and is closed when either:
+//! * A close event for it is read, which is written when `fmt` is configured
+//!   with [`FmtSpan::CLOSE`]. The span's duration is then the `time.busy`
+//!   field of the close event, and its level is that of the close event.
+//! * A later line on the same thread has a span list that doesn't contain it.
+//!   The span's duration is then estimated from the timestamps of the first
+//!   and last lines it contained, and its level defaults to `INFO`.
+//!
+//! Lines are grouped by their `threadId` or `threadName` if present, so
+//! enabling [`with_thread_ids`] helps to separate spans on different threads.
+//! Events from concurrent tasks on the same thread may still be split into
+//! several trees.
+//!
+//! Field values are kept as they were written, so unlike trees from a
+//! `ForestLayer`, string values aren't quoted.
+//!
+//! [`with_span_list`]: tracing_subscriber::fmt::format::Json::with_span_list
+//! [`FmtSpan::CLOSE`]: tracing_subscriber::fmt::format::FmtSpan::CLOSE
+//! [`with_thread_ids`]: tracing_subscriber::fmt::SubscriberBuilder::with_thread_ids
+//! [`ForestLayer`]: crate::ForestLayer
+//!
+//! # Examples
+//!
+//! ```
+//! let logs = r#"
+//! {"timestamp":"2022-03-24T16:00:00.000Z","level":"INFO","fields":{"message":"hello"},"target":"app","span":{"name":"request"},"spans":[{"name":"request"}]}
+//! {"timestamp":"2022-03-24T16:00:00.002Z","level":"INFO","fields":{"message":"close","time.busy":"2.00ms","time.idle":"10.0µs"},"target":"app","span":{"name":"request"},"spans":[]}
+//! "#;
+//!
+//! let trees = tracing_forest::fmt_json::convert(logs)?;
+//!
+//! let request = trees[0].span()?;
+//! assert!(request.name() == "request");
+//! assert!(request.total_duration().as_millis() == 2);
+//! assert!(request.nodes()[0].event()?.message() == Some("hello"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+use crate::tree::{Event, Field, FieldSet, Shared, Span, Tree};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::Level;
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
+
+/// Incrementally reconstructs [`Tree`]s from lines of `tracing_subscriber::fmt`
+/// JSON output.
+///
+/// See the [module-level documentation](self) for more details.
+#[derive(Debug, Default)]
+pub struct Converter {
+    /// The open spans on each thread, from the root.
+    threads: HashMap<Option<String>, Vec<OpenSpan>>,
+    timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct OpenSpan {
+    /// The span as written by `fmt`, used to identify it.
+    json: Map<String, Value>,
+    span: Span,
+    last_seen: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+struct Line {
+    timestamp: Option<String>,
+    level: Option<String>,
+    fields: Option<Map<String, Value>>,
+    span: Option<Map<String, Value>>,
+    spans: Option<Vec<Map<String, Value>>>,
+    #[serde(rename = "threadId")]
+    thread_id: Option<String>,
+    #[serde(rename = "threadName")]
+    thread_name: Option<String>,
+
+    // Ignored, so they aren't mistaken for flattened event fields.
+    #[serde(default, rename = "target")]
+    _target: Value,
+    #[serde(default, rename = "filename")]
+    _filename: Value,
+    #[serde(default, rename = "line_number")]
+    _line_number: Value,
+
+    /// Event fields, if `fmt` was configured with `flatten_event`.
+    #[serde(flatten)]
+    flattened: Map<String, Value>,
+}
+
+/// Reconstructs the [`Tree`]s from a string of `tracing_subscriber::fmt`
+/// JSON output, with one event per line.
+///
+/// # Errors
+///
+/// This function returns an error if a nonempty line isn't a JSON object.
+pub fn convert(input: &str) -> serde_json::Result<Vec<Tree>> {
+    let mut converter = Converter::new();
+    let mut trees = Vec::new();
+    for line in input.lines() {
+        trees.extend(converter.push(line)?);
+    }
+    trees.extend(converter.finish());
+    Ok(trees)
+}
+
+impl Converter {
+    /// Create a `Converter` with no open spans.
+    pub fn new() -> Self {
+        Converter::default()
+    }
+
+    /// Process a line of JSON output, returning the trees that it completed.
+    ///
+    /// Blank lines are ignored.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a nonempty line isn't a JSON object.
+    pub fn push(&mut self, line: &str) -> serde_json::Result<Vec<Tree>> {
+        let mut completed = Vec::new();
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(completed);
+        }
+
+        let line: Line = serde_json::from_str(line)?;
+
+        if let Some(timestamp) = line.timestamp.as_deref().and_then(parse_timestamp) {
+            self.timestamp = timestamp;
+        }
+        let timestamp = self.timestamp;
+        let level = line.level.as_deref().and_then(|level| level.parse().ok());
+        let level = level.unwrap_or(Level::INFO);
+
+        let mut fields = match line.fields {
+            Some(fields) => fields,
+            None => line.flattened,
+        };
+        let message = match fields.remove("message") {
+            Some(Value::String(message)) => Some(message),
+            Some(message) => Some(message.to_string()),
+            None => None,
+        };
+
+        let thread = line.thread_id.or(line.thread_name);
+        let stack = self.threads.entry(thread).or_default();
+
+        // The span list, or else just the current span.
+        let path = match (line.spans, &line.span) {
+            (Some(spans), _) => spans,
+            (None, Some(span)) => vec![span.clone()],
+            (None, None) => Vec::new(),
+        };
+
+        match message.as_deref() {
+            Some("close") if fields.contains_key("time.busy") => {
+                let busy = fields.get("time.busy").and_then(parse_duration);
+                let closing = line.span.unwrap_or_default();
+
+                match stack.iter().rposition(|open| open.json == closing) {
+                    Some(index) => {
+                        // Spans opened within the closing span are implicitly closed.
+                        close(stack, index + 1, &mut completed);
+                        let open = stack.last_mut().expect("closing span is open");
+                        open.last_seen = timestamp;
+                        open.span.shared.level = level;
+                        close_last(stack, busy, &mut completed);
+                    }
+                    None => {
+                        // The span has no events, so it's new. The span list
+                        // holds the spans that were current when it closed.
+                        let mut path = path;
+                        if path.last() == Some(&closing) {
+                            path.pop();
+                        }
+                        sync(stack, path, timestamp, &mut completed);
+
+                        let elapsed = busy.unwrap_or_default()
+                            + fields
+                                .get("time.idle")
+                                .and_then(parse_duration)
+                                .unwrap_or_default();
+                        let opened = chrono::Duration::from_std(elapsed)
+                            .ok()
+                            .and_then(|elapsed| timestamp.checked_sub_signed(elapsed))
+                            .unwrap_or(timestamp);
+
+                        let mut open = OpenSpan::new(closing, opened, stack.last());
+                        open.span.shared.level = level;
+                        open.last_seen = timestamp;
+                        stack.push(open);
+                        close_last(stack, busy, &mut completed);
+                    }
+                }
+            }
+            // Span lifecycle events carry no information of their own.
+            Some("new") | Some("enter") | Some("exit") | Some("close") if fields.is_empty() => {}
+            _ => {
+                sync(stack, path, timestamp, &mut completed);
+
+                let event = Event {
+                    shared: Shared {
+                        #[cfg(feature = "uuid")]
+                        uuid: stack
+                            .last()
+                            .map_or_else(Uuid::nil, |open| open.span.shared.uuid),
+                        timestamp,
+                        level,
+                        fields: to_fields(fields),
+                    },
+                    message,
+                    tag: None,
+                };
+
+                match stack.last_mut() {
+                    Some(open) => open.span.nodes.push(Tree::Event(event)),
+                    None => completed.push(Tree::Event(event)),
+                }
+            }
+        }
+
+        if stack.is_empty() {
+            self.threads.retain(|_, stack| !stack.is_empty());
+        }
+
+        Ok(completed)
+    }
+
+    /// Close all open spans, returning the trees that they completed.
+    ///
+    /// This should be called at the end of the input.
+    pub fn finish(self) -> Vec<Tree> {
+        let mut completed = Vec::new();
+        for (_, mut stack) in self.threads {
+            close(&mut stack, 0, &mut completed);
+        }
+        completed
+    }
+}
+
+impl OpenSpan {
+    fn new(mut json: Map<String, Value>, timestamp: DateTime<Utc>, parent: Option<&Self>) -> Self {
+        let name = match json.get("name") {
+            Some(Value::String(name)) => name.clone(),
+            _ => String::new(),
+        };
+
+        let mut fields = json.clone();
+        fields.remove("name");
+        // Produced by `fmt` when the span's fields couldn't be formatted as JSON.
+        fields.remove("formatted_fields");
+
+        #[cfg(feature = "uuid")]
+        let uuid = match fields.get("uuid") {
+            Some(Value::String(uuid)) => uuid.parse().ok(),
+            _ => None,
+        }
+        .or_else(|| parent.map(|parent| parent.span.shared.uuid))
+        .unwrap_or_else(Uuid::new_v4);
+        #[cfg(not(feature = "uuid"))]
+        let _ = parent;
+
+        // Normalize the identity so that a close event's span matches.
+        json.insert("name".to_string(), Value::String(name.clone()));
+
+        OpenSpan {
+            json,
+            span: Span::new(
+                Shared {
+                    #[cfg(feature = "uuid")]
+                    uuid,
+                    timestamp,
+                    level: Level::INFO,
+                    fields: to_fields(fields),
+                },
+                name,
+            ),
+            last_seen: timestamp,
+        }
+    }
+}
+
+/// Makes the open spans match a span list, closing any spans that aren't in
+/// it and opening any that weren't open.
+fn sync(
+    stack: &mut Vec<OpenSpan>,
+    path: Vec<Map<String, Value>>,
+    timestamp: DateTime<Utc>,
+    completed: &mut Vec<Tree>,
+) {
+    let common = stack
+        .iter()
+        .zip(&path)
+        .take_while(|(open, json)| open.json == **json)
+        .count();
+
+    close(stack, common, completed);
+
+    for open in stack.iter_mut() {
+        open.last_seen = timestamp;
+    }
+
+    for json in path.into_iter().skip(common) {
+        let open = OpenSpan::new(json, timestamp, stack.last());
+        stack.push(open);
+    }
+}
+
+/// Closes the spans above `len` with estimated durations.
+fn close(stack: &mut Vec<OpenSpan>, len: usize, completed: &mut Vec<Tree>) {
+    while stack.len() > len {
+        close_last(stack, None, completed);
+    }
+}
+
+/// Closes the innermost span, with the duration if one is known.
+fn close_last(stack: &mut Vec<OpenSpan>, duration: Option<Duration>, completed: &mut Vec<Tree>) {
+    let open = match stack.pop() {
+        Some(open) => open,
+        None => return,
+    };
+    let last_seen = open.last_seen;
+    let mut span = open.span;
+
+    span.inner_duration = span
+        .nodes
+        .iter()
+        .filter_map(|node| match node {
+            Tree::Span(child) => Some(child.total_duration),
+            Tree::Event(_) => None,
+        })
+        .sum();
+
+    span.closed_at = last_seen;
+    span.lifetime = (last_seen - span.shared.timestamp).to_std().unwrap_or_default();
+    span.total_duration = duration.unwrap_or(span.lifetime);
+
+    // See the comment in `ForestLayer::on_close`.
+    if span.total_duration < span.inner_duration {
+        span.total_duration = span.inner_duration;
+    }
+
+    match stack.last_mut() {
+        Some(parent) => {
+            parent.last_seen = parent.last_seen.max(last_seen);
+            parent.span.nodes.push(Tree::Span(span));
+        }
+        None => completed.push(Tree::Span(span)),
+    }
+}
+
+fn to_fields(fields: Map<String, Value>) -> FieldSet {
+    fields
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::String(value) => value,
+                value => value.to_string(),
+            };
+            Field::new(key, value)
+        })
+        .collect()
+}
+
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+}
+
+/// Parses a duration formatted by `fmt`, like `1.23ms`.
+fn parse_duration(value: &Value) -> Option<Duration> {
+    let value = value.as_str()?;
+    let (number, nanos_per_unit) = [("ns", 1.0), ("µs", 1e3), ("us", 1e3), ("ms", 1e6), ("s", 1e9)]
+        .iter()
+        .find_map(|(unit, nanos)| Some((value.strip_suffix(unit)?, nanos)))?;
+    let nanos = number.parse::<f64>().ok()? * nanos_per_unit;
+    Some(Duration::from_nanos(nanos as u64))
+}
@@ -0,0 +1,76 @@
+//! Policies for processing events immediately.
+//!
+//! Events with the field `immediate = true` are sent to the
+//! [immediate processor] as soon as they occur, in addition to being recorded
+//! in their trace tree. An [`ImmediatePolicy`] makes other events immediate
+//! too, based on their metadata and [`Tag`], without changing any call sites.
+//!
+//! Policies are set with [`ForestLayer::immediate_if`]. This trait is
+//! blanket-implemented for all `Fn(&Metadata, Option<&Tag>) -> bool`, and
+//! [`at_least`] and [`tag_prefix`] cover common cases.
+//!
+//! [immediate processor]: crate::ForestLayer::immediate
+//! [`ForestLayer::immediate_if`]: crate::ForestLayer::immediate_if
+//!
+//! # Examples
+//!
+//! Processing warnings, errors, and security events immediately:
+//! ```
+//! use tracing::Metadata;
+//! use tracing_forest::{immediate, ForestLayer, Tag};
+//!
+//! let warnings = immediate::at_least(tracing::Level::WARN);
+//!
+//! let layer = ForestLayer::default().immediate_if(move |metadata: &Metadata, tag: Option<&Tag>| {
+//!     warnings(metadata, tag) || tag.and_then(|tag| tag.prefix()) == Some("security")
+//! });
+//! # let _ = layer;
+//! ```
+use crate::Tag;
+use tracing::{Level, Metadata};
+
+/// A type that decides whether events are processed immediately.
+///
+/// This trait is blanket-implemented for all `Fn(&Metadata, Option<&Tag>) -> bool`.
+///
+/// See the [module-level documentation](mod@crate::immediate) for more details.
+pub trait ImmediatePolicy: 'static {
+    /// Returns whether an event with the given metadata and [`Tag`] should be
+    /// processed immediately.
+    fn is_immediate(&self, metadata: &Metadata<'_>, tag: Option<&Tag>) -> bool;
+}
+
+/// An `ImmediatePolicy` that only processes events with `immediate = true`
+/// immediately.
+#[derive(Clone, Debug)]
+pub struct ExplicitOnly;
+
+impl ImmediatePolicy for ExplicitOnly {
+    fn is_immediate(&self, _metadata: &Metadata<'_>, _tag: Option<&Tag>) -> bool {
+        false
+    }
+}
+
+impl<F> ImmediatePolicy for F
+where
+    F: 'static + Fn(&Metadata<'_>, Option<&Tag>) -> bool,
+{
+    fn is_immediate(&self, metadata: &Metadata<'_>, tag: Option<&Tag>) -> bool {
+        self(metadata, tag)
+    }
+}
+
+/// Returns a policy that processes events at least as severe as `level`
+/// immediately.
+///
+/// For example, `at_least(Level::WARN)` matches `WARN` and `ERROR` events.
+pub fn at_least(level: Level) -> impl Fn(&Metadata<'_>, Option<&Tag>) -> bool + Clone {
+    // More verbose levels compare as greater.
+    move |metadata, _tag| *metadata.level() <= level
+}
+
+/// Returns a policy that processes events whose [`Tag`] has the given prefix
+/// immediately.
+pub fn tag_prefix(prefix: &'static str) -> impl Fn(&Metadata<'_>, Option<&Tag>) -> bool + Clone {
+    move |_metadata, tag| tag.and_then(|tag| tag.prefix()) == Some(prefix)
+}
@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+/// Returns the CPU time used by the current thread so far, or `None` if it
+/// can't be read.
+pub(crate) fn thread_cpu_time() -> Option<Duration> {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+
+    // SAFETY: `time` is a valid `timespec` for the duration of the call.
+    let result = unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut time) };
+
+    if result == 0 {
+        Some(Duration::new(time.tv_sec as u64, time.tv_nsec as u32))
+    } else {
+        None
+    }
+}
+
@@ -1,12 +1,24 @@
+#[cfg(feature = "alloc-count")]
+use crate::alloc::{self, Allocs};
 use crate::fail;
-use crate::printer::{PrettyPrinter, TestCapturePrinter};
+use crate::immediate::{ExplicitOnly, ImmediatePolicy};
+use crate::limit::{Elided, Limits};
+use crate::printer::{
+    Immediate, ImmediatePrinter, MakeStderr, PrettyPrinter, Printer, TestCapturePrinter,
+};
 use crate::processor::{Processor, Sink};
-use crate::tag::{NoTag, Tag, TagParser};
+use crate::redact::Redaction;
+use crate::tag::{NoTag, TagParser};
 use crate::tree::{self, FieldSet, Tree};
 #[cfg(feature = "chrono")]
 use chrono::Utc;
 use std::fmt;
-use std::io::{self, Write};
+#[cfg(feature = "uuid")]
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+#[cfg(all(feature = "cpu-time", target_os = "linux"))]
+use std::time::Duration;
 use std::time::Instant;
 use tracing::field::{Field, Visit};
 use tracing::span::{Attributes, Id};
@@ -17,16 +29,47 @@ use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::util::TryInitError;
 #[cfg(feature = "uuid")]
 use uuid::Uuid;
+cfg_cpu_time! {
+    mod cpu;
+}
 #[cfg(feature = "uuid")]
 pub(crate) mod id;
 
 pub(crate) struct OpenedSpan {
     span: tree::Span,
+    opened: Instant,
     start: Instant,
+    /// The CPU time of the current thread when the span was last entered.
+    #[cfg(all(feature = "cpu-time", target_os = "linux"))]
+    cpu_start: Option<Duration>,
+    /// The allocations of the current thread when the span was last entered.
+    #[cfg(feature = "alloc-count")]
+    alloc_start: Allocs,
+    /// The allocations made in child spans.
+    #[cfg(feature = "alloc-count")]
+    inner_allocs: Allocs,
+    /// When each node started, if nodes are ordered by start time.
+    starts: Option<Vec<Instant>>,
+    /// The number of nodes recorded in the span's tree, if limited.
+    tree_nodes: Option<Arc<AtomicUsize>>,
+    /// Whether the span has a place in its tree's count of nodes.
+    reserved: bool,
+    /// The number of places in the tree's count held by the span's nodes,
+    /// including their descendants.
+    held: usize,
+    elided: Elided,
 }
 
 impl OpenedSpan {
-    fn new<S>(attrs: &Attributes, _ctx: &Context<S>) -> Self
+    fn new<S>(
+        attrs: &Attributes,
+        _ctx: &Context<S>,
+        redaction: &Redaction,
+        limits: &Limits,
+        tree_nodes: Option<Arc<AtomicUsize>>,
+        reserved: bool,
+        chronological: bool,
+    ) -> Self
     where
         S: Subscriber + for<'a> LookupSpan<'a>,
     {
@@ -50,7 +93,8 @@ impl OpenedSpan {
                 return;
             }
 
-            let value = format!("{:?}", value);
+            let value = redaction.field(field.name(), format!("{:?}", value));
+            let value = limits.field(value);
             fields.push(tree::Field::new(field.name(), value));
         });
 
@@ -73,23 +117,83 @@ impl OpenedSpan {
 
         OpenedSpan {
             span: tree::Span::new(shared, attrs.metadata().name()),
+            opened: Instant::now(),
             start: Instant::now(),
+            #[cfg(all(feature = "cpu-time", target_os = "linux"))]
+            cpu_start: None,
+            #[cfg(feature = "alloc-count")]
+            alloc_start: Allocs::default(),
+            #[cfg(feature = "alloc-count")]
+            inner_allocs: Allocs::default(),
+            starts: chronological.then(Vec::new),
+            tree_nodes,
+            reserved,
+            held: 0,
+            elided: Elided::default(),
         }
     }
 
     fn enter(&mut self) {
         self.start = Instant::now();
+        #[cfg(all(feature = "cpu-time", target_os = "linux"))]
+        {
+            self.cpu_start = cpu::thread_cpu_time();
+        }
+        #[cfg(feature = "alloc-count")]
+        {
+            self.alloc_start = Allocs::current();
+        }
     }
 
     fn exit(&mut self) {
-        self.span.total_duration += self.start.elapsed();
+        let elapsed = self.start.elapsed();
+        self.span.total_duration += elapsed;
+        self.span.entries += 1;
+        self.span.max_entry_duration = self.span.max_entry_duration.max(elapsed);
+        #[cfg(all(feature = "cpu-time", target_os = "linux"))]
+        if let (Some(start), Some(now)) = (self.cpu_start.take(), cpu::thread_cpu_time()) {
+            self.span.cpu_time += now.saturating_sub(start);
+        }
+        #[cfg(feature = "alloc-count")]
+        {
+            let allocs = Allocs::current().saturating_sub(self.alloc_start);
+            self.span.alloc_count += allocs.count;
+            self.span.alloc_bytes += allocs.bytes;
+        }
     }
 
-    fn close(self) -> tree::Span {
+    fn close(mut self) -> tree::Span {
+        if let Some(summary) = self.elided.summary(&self.span.shared) {
+            self.span.nodes.push(Tree::Event(summary));
+        }
+        #[cfg(feature = "chrono")]
+        {
+            self.span.closed_at = Utc::now();
+        }
+        self.span.lifetime = self.opened.elapsed();
+        #[cfg(feature = "alloc-count")]
+        {
+            let allocs = self.span.allocs().saturating_sub(self.inner_allocs);
+            self.span.self_alloc_count = allocs.count;
+            self.span.self_alloc_bytes = allocs.bytes;
+        }
         self.span
     }
 
-    fn record_event(&mut self, event: tree::Event) {
+    /// Returns a copy of the span without its nodes, for giving context to
+    /// immediate events.
+    fn shell(&self) -> tree::Span {
+        let mut shell = tree::Span::new(self.span.shared.clone(), self.span.name.clone());
+        #[cfg(feature = "uuid")]
+        {
+            shell.id = self.span.id;
+        }
+        shell.total_duration = self.span.total_duration;
+        shell.inner_duration = self.span.inner_duration;
+        shell
+    }
+
+    fn record_event(&mut self, event: tree::Event, limits: &Limits) {
         #[cfg(feature = "uuid")]
         let event = {
             let mut event = event;
@@ -97,12 +201,68 @@ impl OpenedSpan {
             event
         };
 
-        self.span.nodes.push(Tree::Event(event));
+        self.record(Tree::Event(event), Instant::now(), false, 0, limits);
     }
 
-    fn record_span(&mut self, span: tree::Span) {
+    fn record_span(
+        &mut self,
+        span: tree::Span,
+        opened: Instant,
+        reserved: bool,
+        held: usize,
+        limits: &Limits,
+    ) {
         self.span.inner_duration += span.total_duration();
-        self.span.nodes.push(Tree::Span(span));
+        #[cfg(feature = "alloc-count")]
+        self.inner_allocs.add(span.allocs());
+        self.record(Tree::Span(span), opened, reserved, held, limits);
+    }
+
+    /// Records a node, or elides it if the span or its tree is full.
+    ///
+    /// `held` is the number of places in the tree's count held by the node's
+    /// descendants, which are given back along with the node's own place if
+    /// it's elided.
+    fn record(&mut self, tree: Tree, start: Instant, reserved: bool, held: usize, limits: &Limits) {
+        if self.is_full(reserved, limits) {
+            self.release(usize::from(reserved) + held);
+            self.elided.add(&tree);
+            return;
+        }
+        self.held += 1 + held;
+
+        match &mut self.starts {
+            Some(starts) => {
+                // Nodes are recorded when they complete, so a span may have
+                // started before nodes that were recorded ahead of it.
+                let index = starts.partition_point(|recorded| *recorded <= start);
+                starts.insert(index, start);
+                self.span.nodes.insert(index, tree);
+            }
+            None => self.span.nodes.push(tree),
+        }
+    }
+
+    /// Returns whether the span or its tree can't fit another node, reserving
+    /// a place in the tree if it can and the node doesn't have one already.
+    fn is_full(&self, reserved: bool, limits: &Limits) -> bool {
+        if let Some(max) = limits.max_children {
+            if self.span.nodes.len() >= max {
+                return true;
+            }
+        }
+
+        match (&self.tree_nodes, limits.max_nodes) {
+            (Some(tree_nodes), Some(max)) if !reserved => !reserve(tree_nodes, max),
+            _ => false,
+        }
+    }
+
+    /// Gives back places in the tree's count of nodes.
+    fn release(&self, places: usize) {
+        if let Some(tree_nodes) = &self.tree_nodes {
+            tree_nodes.fetch_sub(places, Ordering::Relaxed);
+        }
     }
 
     #[cfg(feature = "uuid")]
@@ -113,16 +273,174 @@ impl OpenedSpan {
 
 /// A [`Layer`] that collects and processes trace data while preserving
 /// contextual coherence.
+///
+/// Events with the field `immediate = true` are also sent to a separate
+/// processor as soon as they occur, nested inside copies of the spans they
+/// occurred in. By default, these are printed to stderr with the [`Immediate`]
+/// formatter, and the layer panics if writing fails. Use
+/// [`ForestLayer::immediate`] to send them elsewhere, and
+/// [`ForestLayer::immediate_if`] to make other events immediate too.
+///
+/// [`Immediate`]: crate::printer::Immediate
 #[derive(Clone, Debug)]
-pub struct ForestLayer<P, T> {
+pub struct ForestLayer<P, T, I = ImmediatePrinter, M = ExplicitOnly> {
     processor: P,
     tag: T,
+    immediate: I,
+    policy: M,
+    redaction: Redaction,
+    limits: Limits,
+    chronological: bool,
 }
 
 impl<P: Processor, T: TagParser> ForestLayer<P, T> {
     /// Create a new `ForestLayer` from a [`Processor`] and a [`TagParser`].
     pub fn new(processor: P, tag: T) -> Self {
-        ForestLayer { processor, tag }
+        ForestLayer {
+            processor,
+            tag,
+            immediate: Printer::new().formatter(Immediate).writer(MakeStderr),
+            policy: ExplicitOnly,
+            redaction: Redaction::new(),
+            limits: Limits::new(),
+            chronological: false,
+        }
+    }
+}
+
+impl<P, T, I, M> ForestLayer<P, T, I, M> {
+    /// Set the [`Processor`] for events with `immediate = true`.
+    ///
+    /// The processor receives a [`Tree`] for each immediate event, where the
+    /// event is nested inside copies of the spans it occurred in, from the
+    /// root span down. The copies contain no other nodes, and their durations
+    /// only include time the spans have been entered so far. The event is
+    /// still recorded in the trace tree as usual.
+    ///
+    /// Errors can be handled by adding fallbacks with [`Processor::or`]. If
+    /// processing still fails, the layer panics.
+    ///
+    /// # Examples
+    ///
+    /// Writing immediate events with the [`Compact`] formatter:
+    /// ```
+    /// use tracing_forest::printer::{Compact, MakeStderr};
+    /// use tracing_forest::{ForestLayer, Printer};
+    ///
+    /// let compact = Compact::new().spans(false);
+    /// let layer = ForestLayer::default()
+    ///     .immediate(Printer::new().formatter(compact).writer(MakeStderr));
+    /// # let _ = layer;
+    /// ```
+    ///
+    /// Writing immediate events as JSON alerts, and ignoring failures:
+    /// ```
+    /// # #[cfg(feature = "serde")]
+    /// # {
+    /// use tracing_forest::{printer::MakeStderr, tree::Tree, ForestLayer, Printer};
+    /// use tracing_forest::traits::*;
+    ///
+    /// fn json(tree: &Tree) -> serde_json::Result<String> {
+    ///     serde_json::to_string(tree).map(|json| json + "\n")
+    /// }
+    ///
+    /// let layer = ForestLayer::default()
+    ///     .immediate(Printer::new().formatter(json).writer(MakeStderr).or_none());
+    /// # let _ = layer;
+    /// # }
+    /// ```
+    ///
+    /// [`Compact`]: crate::printer::Compact
+    pub fn immediate<I2>(self, immediate: I2) -> ForestLayer<P, T, I2, M>
+    where
+        I2: Processor,
+    {
+        ForestLayer {
+            processor: self.processor,
+            tag: self.tag,
+            immediate,
+            policy: self.policy,
+            redaction: self.redaction,
+            limits: self.limits,
+            chronological: self.chronological,
+        }
+    }
+
+    /// Set an [`ImmediatePolicy`] deciding which events are processed
+    /// immediately, in addition to those with `immediate = true`.
+    ///
+    /// The policy is given the event's metadata and the [`Tag`] parsed from
+    /// it. Immediate events are still recorded in the trace tree as usual.
+    ///
+    /// See the [`immediate`] module for details.
+    ///
+    /// [`Tag`]: crate::Tag
+    /// [`immediate`]: crate::immediate
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tracing::Level;
+    /// use tracing_forest::{immediate, ForestLayer};
+    ///
+    /// let layer = ForestLayer::default().immediate_if(immediate::at_least(Level::WARN));
+    /// # let _ = layer;
+    /// ```
+    pub fn immediate_if<M2>(self, policy: M2) -> ForestLayer<P, T, I, M2>
+    where
+        M2: ImmediatePolicy,
+    {
+        ForestLayer {
+            processor: self.processor,
+            tag: self.tag,
+            immediate: self.immediate,
+            policy,
+            redaction: self.redaction,
+            limits: self.limits,
+            chronological: self.chronological,
+        }
+    }
+
+    /// Set the [`Redaction`] applied to fields as they're recorded, so that
+    /// redacted values never reach any processor.
+    ///
+    /// See the [`redact`] module for details.
+    ///
+    /// [`redact`]: crate::redact
+    pub fn redact(mut self, redaction: Redaction) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    /// Set the [`Limits`] on the size of recorded data.
+    ///
+    /// See the [`limit`] module for details.
+    ///
+    /// [`limit`]: crate::limit
+    pub fn limit(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Set whether the nodes of each span are ordered by when they started.
+    ///
+    /// By default, nodes are in the order they completed: events when they
+    /// occur, and spans when they close. When child spans run concurrently, or
+    /// outlive events that occur after they open, this doesn't reflect the
+    /// order things happened in. Ordering by start time places each span where
+    /// it opened instead, so traces read in causal order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tracing_forest::ForestLayer;
+    ///
+    /// let layer = ForestLayer::default().chronological(true);
+    /// # let _ = layer;
+    /// ```
+    pub fn chronological(mut self, chronological: bool) -> Self {
+        self.chronological = chronological;
+        self
     }
 }
 
@@ -141,35 +459,71 @@ impl ForestLayer<Sink, NoTag> {
 
 impl Default for ForestLayer<PrettyPrinter, NoTag> {
     fn default() -> Self {
-        ForestLayer {
-            processor: PrettyPrinter::new(),
-            tag: NoTag,
-        }
+        ForestLayer::new(PrettyPrinter::new(), NoTag)
     }
 }
 
-impl<P, T, S> Layer<S> for ForestLayer<P, T>
+impl<P, T, I, M, S> Layer<S> for ForestLayer<P, T, I, M>
 where
     P: Processor,
     T: TagParser,
+    I: Processor,
+    M: ImmediatePolicy,
     S: Subscriber + for<'a> LookupSpan<'a>,
 {
     fn on_new_span(&self, attrs: &Attributes, id: &Id, ctx: Context<S>) {
+        // Allocations made while recording aren't attributed to any span.
+        #[cfg(feature = "alloc-count")]
+        let _paused = alloc::pause();
+
         let span = ctx.span(id).expect(fail::SPAN_NOT_IN_CONTEXT);
-        let opened = OpenedSpan::new(attrs, &ctx);
+
+        // Spans in the same tree share a count of its nodes. Child spans take
+        // their place when opened, so that the events inside them aren't
+        // recorded only to be dropped with the span.
+        let (tree_nodes, reserved) = match (self.limits.max_nodes, span.parent()) {
+            (Some(max), Some(parent)) => {
+                let tree_nodes = parent
+                    .extensions()
+                    .get::<OpenedSpan>()
+                    .expect(fail::OPENED_SPAN_NOT_IN_EXTENSIONS)
+                    .tree_nodes
+                    .clone()
+                    .unwrap_or_default();
+                let reserved = reserve(&tree_nodes, max);
+                (Some(tree_nodes), reserved)
+            }
+            (Some(_), None) => (Some(Arc::default()), true),
+            (None, _) => (None, true),
+        };
+
+        let opened = OpenedSpan::new(
+            attrs,
+            &ctx,
+            &self.redaction,
+            &self.limits,
+            tree_nodes,
+            reserved,
+            self.chronological,
+        );
 
         let mut extensions = span.extensions_mut();
         extensions.insert(opened);
     }
 
     fn on_event(&self, event: &Event, ctx: Context<S>) {
-        struct Visitor {
+        #[cfg(feature = "alloc-count")]
+        let _paused = alloc::pause();
+
+        struct Visitor<'a> {
             message: Option<String>,
             fields: FieldSet,
             immediate: bool,
+            redaction: &'a Redaction,
+            limits: &'a Limits,
         }
 
-        impl Visit for Visitor {
+        impl Visit for Visitor<'_> {
             fn record_bool(&mut self, field: &Field, value: bool) {
                 match field.name() {
                     "immediate" => self.immediate |= value,
@@ -180,8 +534,14 @@ where
             fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
                 let value = format!("{:?}", value);
                 match field.name() {
-                    "message" if self.message.is_none() => self.message = Some(value),
-                    key => self.fields.push(tree::Field::new(key, value)),
+                    "message" if self.message.is_none() => {
+                        let message = self.redaction.message(value);
+                        self.message = Some(self.limits.message(message))
+                    }
+                    key => {
+                        let value = self.limits.field(self.redaction.field(key, value));
+                        self.fields.push(tree::Field::new(key, value))
+                    }
                 }
             }
         }
@@ -190,6 +550,8 @@ where
             message: None,
             fields: FieldSet::default(),
             immediate: false,
+            redaction: &self.redaction,
+            limits: &self.limits,
         };
 
         event.record(&mut visitor);
@@ -203,16 +565,22 @@ where
             fields: visitor.fields,
         };
 
+        let tag = self.tag.parse(event);
+        let immediate =
+            visitor.immediate || self.policy.is_immediate(event.metadata(), tag.as_ref());
+
         let tree_event = tree::Event {
             shared,
             message: visitor.message,
-            tag: self.tag.parse(event),
+            tag,
         };
 
         let current_span = ctx.event_span(event);
 
-        if visitor.immediate {
-            write_immediate(&tree_event, current_span.as_ref()).expect("writing urgent failed");
+        if immediate {
+            self.immediate
+                .process(immediate_tree(&tree_event, current_span.as_ref()))
+                .expect(fail::PROCESSING_ERROR);
         }
 
         match current_span.as_ref() {
@@ -220,7 +588,7 @@ where
                 .extensions_mut()
                 .get_mut::<OpenedSpan>()
                 .expect(fail::OPENED_SPAN_NOT_IN_EXTENSIONS)
-                .record_event(tree_event),
+                .record_event(tree_event, &self.limits),
             None => self
                 .processor
                 .process(Tree::Event(tree_event))
@@ -228,7 +596,40 @@ where
         }
     }
 
+    #[cfg(feature = "uuid")]
+    fn on_follows_from(&self, id: &Id, follows: &Id, ctx: Context<S>) {
+        #[cfg(feature = "alloc-count")]
+        let _paused = alloc::pause();
+
+        // The span being followed from may have already closed.
+        let follows_id = match ctx.span(follows) {
+            Some(follows) => {
+                follows
+                    .extensions()
+                    .get::<OpenedSpan>()
+                    .expect(fail::OPENED_SPAN_NOT_IN_EXTENSIONS)
+                    .span
+                    .id
+            }
+            None => return,
+        };
+
+        let span = ctx.span(id).expect(fail::SPAN_NOT_IN_CONTEXT);
+        let mut extensions = span.extensions_mut();
+        let follows_from = &mut extensions
+            .get_mut::<OpenedSpan>()
+            .expect(fail::OPENED_SPAN_NOT_IN_EXTENSIONS)
+            .span
+            .follows_from;
+        if !follows_from.contains(&follows_id) {
+            follows_from.push(follows_id);
+        }
+    }
+
     fn on_enter(&self, id: &Id, ctx: Context<S>) {
+        #[cfg(feature = "alloc-count")]
+        let _paused = alloc::pause();
+
         ctx.span(id)
             .expect(fail::SPAN_NOT_IN_CONTEXT)
             .extensions_mut()
@@ -238,6 +639,9 @@ where
     }
 
     fn on_exit(&self, id: &Id, ctx: Context<S>) {
+        #[cfg(feature = "alloc-count")]
+        let _paused = alloc::pause();
+
         ctx.span(id)
             .expect(fail::SPAN_NOT_IN_CONTEXT)
             .extensions_mut()
@@ -247,13 +651,17 @@ where
     }
 
     fn on_close(&self, id: Id, ctx: Context<S>) {
+        #[cfg(feature = "alloc-count")]
+        let _paused = alloc::pause();
+
         let span_ref = ctx.span(&id).expect(fail::SPAN_NOT_IN_CONTEXT);
 
-        let mut span = span_ref
+        let opened = span_ref
             .extensions_mut()
             .remove::<OpenedSpan>()
-            .expect(fail::OPENED_SPAN_NOT_IN_EXTENSIONS)
-            .close();
+            .expect(fail::OPENED_SPAN_NOT_IN_EXTENSIONS);
+        let (started, reserved, held) = (opened.opened, opened.reserved, opened.held);
+        let mut span = opened.close();
 
         // Ensure that the total duration is at least as much as the inner
         // duration. This is caused by when a child span is manually passed
@@ -271,7 +679,7 @@ where
                 .extensions_mut()
                 .get_mut::<OpenedSpan>()
                 .expect(fail::OPENED_SPAN_NOT_IN_EXTENSIONS)
-                .record_span(span),
+                .record_span(span, started, reserved, held, &self.limits),
             None => self
                 .processor
                 .process(Tree::Span(span))
@@ -280,55 +688,41 @@ where
     }
 }
 
-fn write_immediate<S>(event: &tree::Event, current: Option<&SpanRef<S>>) -> io::Result<()>
+/// Takes a place in a tree's count of nodes, returning whether there was room.
+fn reserve(tree_nodes: &AtomicUsize, max: usize) -> bool {
+    tree_nodes
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+            (n < max).then_some(n + 1)
+        })
+        .is_ok()
+}
+
+/// Returns a copy of an event nested inside shells of the spans it occurred in.
+fn immediate_tree<S>(event: &tree::Event, current: Option<&SpanRef<S>>) -> Tree
 where
     S: for<'a> LookupSpan<'a>,
 {
-    // uuid timestamp LEVEL root > inner > leaf > my message here | key: val
-    #[cfg(feature = "smallvec")]
-    let mut writer = smallvec::SmallVec::<[u8; 256]>::new();
-    #[cfg(not(feature = "smallvec"))]
-    let mut writer = Vec::with_capacity(256);
+    let mut tree = Tree::Event(event.clone());
 
-    #[cfg(feature = "uuid")]
-    if let Some(span) = current {
-        let uuid = span
-            .extensions()
-            .get::<OpenedSpan>()
-            .expect(fail::OPENED_SPAN_NOT_IN_EXTENSIONS)
-            .span
-            .uuid();
-        write!(writer, "{} ", uuid)?;
-    }
-
-    #[cfg(feature = "chrono")]
-    write!(writer, "{} ", event.timestamp().to_rfc3339())?;
-
-    write!(writer, "{:<8} ", event.level())?;
-
-    let tag = event.tag().unwrap_or_else(|| Tag::from(event.level()));
+    if let Some(current) = current {
+        for ancestor in current.scope() {
+            let mut span = ancestor
+                .extensions()
+                .get::<OpenedSpan>()
+                .expect(fail::OPENED_SPAN_NOT_IN_EXTENSIONS)
+                .shell();
 
-    write!(writer, "{icon} IMMEDIATE {icon} ", icon = tag.icon())?;
+            #[cfg(feature = "uuid")]
+            if let Tree::Event(event) = &mut tree {
+                event.shared.uuid = span.uuid();
+            }
 
-    if let Some(span) = current {
-        for ancestor in span.scope().from_root() {
-            write!(writer, "{} > ", ancestor.name())?;
+            span.nodes.push(tree);
+            tree = Tree::Span(span);
         }
     }
 
-    // we should just do pretty printing here.
-
-    if let Some(message) = event.message() {
-        write!(writer, "{}", message)?;
-    }
-
-    for field in event.fields().iter() {
-        write!(writer, " | {}: {}", field.key(), field.value())?;
-    }
-
-    writeln!(writer)?;
-
-    io::stderr().write_all(&writer)
+    tree
 }
 
 /// Initializes a global subscriber with a [`ForestLayer`] using the default configuration.
@@ -35,15 +35,13 @@
 //! The easiest way to get started is to enable all features. Do this by
 //! adding the following to your `Cargo.toml` file:
 //! ```toml
-//! tracing-forest = { version = "0.1.5", features = ["full"] }
+//! tracing-forest = { version = "0.2.0", features = ["full"] }
 //! ```
 //! Then, add [`tracing_forest::init`](crate::init) to your main function:
 //! ```
-//! fn main() {
-//!     // Initialize a default `ForestLayer` subscriber
-//!     tracing_forest::init();
-//!     // ...
-//! }
+//! // Initialize a default `ForestLayer` subscriber
+//! tracing_forest::init();
+//! // ...
 //! ```
 //! This crate also provides tools for much more advanced configurations:
 //! ```
@@ -228,6 +226,11 @@
 //! event and its parent spans to stderr. Unlike `eprintln!`, the event will
 //! still appear in the trace tree written once the root span closes.
 //!
+//! Immediate events can be sent to any [`Processor`] instead, such as a JSON
+//! alert log or a buffer in tests, using [`ForestLayer::immediate`]. Events can
+//! also be made immediate by their level or [`Tag`] without an `immediate`
+//! field, using [`ForestLayer::immediate_if`].
+//!
 //! ## Example
 //!
 //! ```
@@ -253,7 +256,7 @@
 //!
 //! This crate uses feature flags to reduce dependency bloat.
 //!
-//! * `full`: Enables all features listed below.
+//! * `full`: Enables all features listed below, except `sqlite`.
 //! * `uuid`: Enables spans to carry operation IDs.
 //! * `chrono`: Enables timestamps on trace data.
 //! * `ansi`: Enables ANSI terminal colors.
@@ -261,12 +264,28 @@
 //! * `tokio`: Enables [`worker_task`] and [`capture`].
 //! * `serde`: Enables log trees to be serialized, which is [useful for formatting][serde_fmt].
 //! * `env-filter`: Re-exports [`EnvFilter`] from the [`util`] module.
+//! * `rolling-file`: Enables [`RollingFile`] for writing to rotating log files.
+//! * `gzip`: Enables compressing rotated files written by [`RollingFile`].
+//! * `forward`: Enables [`Forward`] for sending log trees over a socket as NDJSON.
+//! * `fmt-json`: Enables [converting] the JSON output of `tracing_subscriber::fmt` into log trees.
+//! * `sqlite`: Enables [`Sqlite`] for storing log trees in a SQLite database.
+//!   This links against the system's SQLite library, so it isn't part of `full`.
+//! * `regex`: Enables [redacting] fields by regular expression, and redacting event messages.
+//! * `cpu-time`: Enables recording the [CPU time] used by each span, on Linux.
+//! * `alloc-count`: Enables [`CountingAlloc`] for recording the heap allocations made in each span.
 //!
 //! By default, only `smallvec` in enabled.
 //!
 //! [`Uuid`]: uuid::Uuid
 //! [serde_fmt]: crate::printer::Formatter#examples
 //! [`EnvFilter`]: tracing_subscriber::EnvFilter
+//! [`RollingFile`]: crate::printer::RollingFile
+//! [`Forward`]: crate::processor::forward::Forward
+//! [converting]: crate::fmt_json
+//! [`Sqlite`]: crate::processor::sqlite::Sqlite
+//! [redacting]: crate::redact::Redaction
+//! [CPU time]: crate::tree::Span::cpu_time
+//! [`CountingAlloc`]: crate::alloc::CountingAlloc
 
 #![doc(issue_tracker_base_url = "https://github.com/QnnOkabayashi/tracing-forest/issues")]
 #![cfg_attr(
@@ -282,8 +301,13 @@
 #![warn(unused_extern_crates)]
 #![warn(missing_docs)]
 
+pub mod collapse;
+pub mod diff;
+pub mod immediate;
+pub mod limit;
 pub mod printer;
 pub mod processor;
+pub mod redact;
 pub mod tag;
 pub mod tree;
 #[macro_use]
@@ -293,7 +317,7 @@ mod layer;
 
 pub use layer::{init, test_init, ForestLayer};
 pub use printer::{Formatter, PrettyPrinter, Printer};
-pub use processor::Processor;
+pub use processor::{AsyncProcessor, Processor};
 pub use tag::Tag;
 
 cfg_tokio! {
@@ -301,6 +325,14 @@ cfg_tokio! {
     pub use runtime::{capture, worker_task};
 }
 
+cfg_fmt_json! {
+    pub mod fmt_json;
+}
+
+cfg_alloc_count! {
+    pub mod alloc;
+}
+
 cfg_uuid! {
     pub use layer::id::id;
 }
@@ -0,0 +1,236 @@
+//! Bound the memory used by large or busy trees.
+//!
+//! See [`Limits`] for more details.
+use crate::tree::{self, FieldSet, Tree};
+use std::fmt::{self, Write};
+use tracing::Level;
+
+/// Caps on the size of recorded data.
+///
+/// Limits are applied by the [`ForestLayer`] as data is recorded, so memory
+/// stays bounded even while a span that logs in a loop is still open. By
+/// default, nothing is limited.
+///
+/// * Field values and event messages longer than their limit are cut short
+///   and end with `…`, which counts toward the limit. Lengths are in bytes,
+///   and never split a character.
+/// * Once a span has the maximum number of children, or its tree has the
+///   maximum number of nodes, further events and spans are dropped. When the
+///   span closes, a summary event is added in their place, like
+///   `…4,213 events elided (312 WARN)`. Its level is that of the most severe
+///   dropped event, and it has `elided_events` and `elided_spans` fields with
+///   the counts.
+///
+/// [`ForestLayer`]: crate::ForestLayer
+///
+/// # Examples
+///
+/// ```
+/// use tracing_forest::limit::Limits;
+/// use tracing_forest::ForestLayer;
+///
+/// let limits = Limits::new()
+///     .max_field_len(1024)
+///     .max_message_len(4096)
+///     .max_children(1000)
+///     .max_nodes(10_000);
+///
+/// let layer = ForestLayer::default().limit(limits);
+/// # let _ = layer;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Limits {
+    pub(crate) max_field_len: Option<usize>,
+    pub(crate) max_message_len: Option<usize>,
+    pub(crate) max_children: Option<usize>,
+    pub(crate) max_nodes: Option<usize>,
+}
+
+impl Limits {
+    /// Returns new `Limits` that don't limit anything.
+    pub const fn new() -> Self {
+        Limits {
+            max_field_len: None,
+            max_message_len: None,
+            max_children: None,
+            max_nodes: None,
+        }
+    }
+
+    /// Set the maximum length of field values, in bytes.
+    pub fn max_field_len(mut self, max: usize) -> Self {
+        self.max_field_len = Some(max);
+        self
+    }
+
+    /// Set the maximum length of event messages, in bytes.
+    pub fn max_message_len(mut self, max: usize) -> Self {
+        self.max_message_len = Some(max);
+        self
+    }
+
+    /// Set the maximum number of events and spans directly inside a span,
+    /// not counting the summary of dropped nodes.
+    pub fn max_children(mut self, max: usize) -> Self {
+        self.max_children = Some(max);
+        self
+    }
+
+    /// Set the maximum number of events and spans in a tree, not counting its
+    /// root span or summaries of dropped nodes.
+    pub fn max_nodes(mut self, max: usize) -> Self {
+        self.max_nodes = Some(max);
+        self
+    }
+
+    pub(crate) fn field(&self, value: String) -> String {
+        truncate(value, self.max_field_len)
+    }
+
+    pub(crate) fn message(&self, message: String) -> String {
+        truncate(message, self.max_message_len)
+    }
+}
+
+/// Marks where a value was cut short.
+const TRUNCATED: char = '…';
+
+fn truncate(mut value: String, max: Option<usize>) -> String {
+    if let Some(max) = max {
+        if value.len() > max {
+            // Limits too short to fit the marker cut the value without it.
+            let marker = Some(TRUNCATED).filter(|marker| marker.len_utf8() <= max);
+            let mut end = max - marker.map_or(0, char::len_utf8);
+            while !value.is_char_boundary(end) {
+                end -= 1;
+            }
+            value.truncate(end);
+            value.extend(marker);
+        }
+    }
+    value
+}
+
+/// Counts of nodes dropped from a span.
+#[derive(Debug, Default)]
+pub(crate) struct Elided {
+    spans: usize,
+    /// Events, indexed by level from `ERROR` to `TRACE`.
+    events: [usize; 5],
+}
+
+impl Elided {
+    pub(crate) fn add(&mut self, tree: &Tree) {
+        match tree {
+            Tree::Event(event) => self.events[index(event.level())] += 1,
+            Tree::Span(_) => self.spans += 1,
+        }
+    }
+
+    /// Returns an event summarizing the dropped nodes, if there are any.
+    pub(crate) fn summary(&self, shared: &tree::Shared) -> Option<tree::Event> {
+        let events: usize = self.events.iter().sum();
+        if events == 0 && self.spans == 0 {
+            return None;
+        }
+
+        let mut message = String::from("…");
+        // Writing to a `String` is infallible.
+        let _ = self.write_message(events, &mut message);
+
+        let level = LEVELS
+            .iter()
+            .zip(self.events)
+            .find(|(_, count)| *count > 0)
+            .map_or(shared.level, |(level, _)| *level);
+
+        let mut fields = FieldSet::default();
+        fields.push(tree::Field::new("elided_events", events.to_string()));
+        fields.push(tree::Field::new("elided_spans", self.spans.to_string()));
+
+        Some(tree::Event {
+            shared: tree::Shared {
+                #[cfg(feature = "uuid")]
+                uuid: shared.uuid,
+                #[cfg(feature = "chrono")]
+                timestamp: chrono::Utc::now(),
+                level,
+                fields,
+            },
+            message: Some(message),
+            tag: None,
+        })
+    }
+
+    fn write_message(&self, events: usize, writer: &mut String) -> fmt::Result {
+        if events > 0 {
+            write!(writer, "{} {}", Count(events), plural(events, "event"))?;
+            if self.spans > 0 {
+                writer.write_str(" and ")?;
+            }
+        }
+        if self.spans > 0 {
+            write!(
+                writer,
+                "{} {}",
+                Count(self.spans),
+                plural(self.spans, "span")
+            )?;
+        }
+        writer.write_str(" elided")?;
+
+        // Only call out levels that are likely to matter.
+        let mut notable = LEVELS[..2]
+            .iter()
+            .zip(self.events)
+            .filter(|(_, count)| *count > 0)
+            .peekable();
+        if notable.peek().is_some() {
+            writer.write_str(" (")?;
+            for (n, (level, count)) in notable.enumerate() {
+                if n > 0 {
+                    writer.write_str(", ")?;
+                }
+                write!(writer, "{} {}", Count(count), level)?;
+            }
+            writer.write_char(')')?;
+        }
+        Ok(())
+    }
+}
+
+const LEVELS: [Level; 5] = [
+    Level::ERROR,
+    Level::WARN,
+    Level::INFO,
+    Level::DEBUG,
+    Level::TRACE,
+];
+
+fn index(level: Level) -> usize {
+    LEVELS.iter().position(|l| *l == level).unwrap_or(0)
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        noun.to_string()
+    } else {
+        format!("{}s", noun)
+    }
+}
+
+/// Displays a count with thousands separators, like `4,213`.
+struct Count(usize);
+
+impl fmt::Display for Count {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.0.to_string();
+        for (n, digit) in digits.chars().enumerate() {
+            if n > 0 && (digits.len() - n) % 3 == 0 {
+                f.write_char(',')?;
+            }
+            f.write_char(digit)?;
+        }
+        Ok(())
+    }
+}
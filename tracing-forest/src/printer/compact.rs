@@ -0,0 +1,175 @@
+use crate::printer::DurationDisplay;
+use crate::printer::Formatter;
+use crate::tree::{Event, Field, Shared, Span, Tree};
+use std::fmt::{self, Write};
+
+/// Format logs as single lines, prefixed by the path of spans they occurred in.
+///
+/// Each event is written on its own line, and each span is summarized on a line
+/// after its contents, as if written when the span closed. This suits
+/// `grep`-based workflows and log systems that can't handle multi-line records.
+///
+/// Events have the following format:
+/// ```txt
+/// <UUID> <TIMESTAMP> <LEVEL> <ROOT> > ... > <LEAF> > [<TAG>] <MESSAGE> | <KEY>: <VALUE>
+/// ```
+/// And spans have the following format:
+/// ```txt
+/// <UUID> <TIMESTAMP> <LEVEL> <ROOT> > ... > <NAME> [ <DURATION> | <BODY> / <ROOT> ] | <KEY>: <VALUE>
+/// ```
+/// Line breaks in messages and field values are escaped as `\n`, so every
+/// record is exactly one line.
+///
+/// The `UUID` and `TIMESTAMP` are only written if the `uuid` and `chrono`
+/// features are enabled, and the tag is only written if the event was tagged.
+/// See [`Pretty`] for how to interpret span times.
+///
+/// [`Pretty`]: crate::printer::Pretty
+///
+/// # Examples
+///
+/// ```log
+/// INFO     try_from_entry_ro > server::internal_search > [filter.info] Some filter info...
+/// ERROR    try_from_entry_ro > server::internal_search > server::search > [admin.error] On no, an admin error occurred :(
+/// INFO     try_from_entry_ro > server::internal_search > server::search [ 226µs | 10.11% / 70.01% ]
+/// INFO     try_from_entry_ro > server::internal_search [ 296µs | 19.02% / 91.53% ]
+/// TRACE    try_from_entry_ro > [trace] Finished! | count: 3
+/// INFO     try_from_entry_ro [ 324µs | 8.47% / 100.00% ]
+/// ```
+#[derive(Clone, Debug)]
+pub struct Compact {
+    spans: bool,
+}
+
+impl Compact {
+    /// Returns a new compact formatter that writes lines for both spans and
+    /// events.
+    pub const fn new() -> Self {
+        Compact { spans: true }
+    }
+
+    /// Set whether to write a summary line for each span.
+    ///
+    /// If `false`, only events are written.
+    pub fn spans(self, spans: bool) -> Self {
+        Compact { spans }
+    }
+
+    fn format_tree(
+        &self,
+        tree: &Tree,
+        duration_root: Option<f64>,
+        path: &mut String,
+        writer: &mut String,
+    ) -> fmt::Result {
+        match tree {
+            Tree::Event(event) => Compact::format_event(event, path, writer),
+            Tree::Span(span) => self.format_span(span, duration_root, path, writer),
+        }
+    }
+
+    fn format_shared(shared: &Shared, writer: &mut String) -> fmt::Result {
+        #[cfg(feature = "uuid")]
+        write!(writer, "{} ", shared.uuid)?;
+
+        #[cfg(feature = "chrono")]
+        write!(writer, "{} ", shared.timestamp.to_rfc3339())?;
+
+        write!(writer, "{:<8} ", shared.level)
+    }
+
+    fn format_event(event: &Event, path: &str, writer: &mut String) -> fmt::Result {
+        Compact::format_shared(&event.shared, writer)?;
+        writer.write_str(path)?;
+
+        if let Some(tag) = event.tag() {
+            write!(writer, "[{}] ", tag)?;
+        }
+
+        if let Some(message) = event.message() {
+            write!(writer, "{}", OneLine(message))?;
+        }
+
+        Compact::format_fields(event.fields(), writer)
+    }
+
+    fn format_span(
+        &self,
+        span: &Span,
+        duration_root: Option<f64>,
+        path: &mut String,
+        writer: &mut String,
+    ) -> fmt::Result {
+        let total_duration = span.total_duration().as_nanos() as f64;
+        let root_duration = duration_root.unwrap_or(total_duration);
+
+        let parent_len = path.len();
+        write!(path, "{} > ", span.name())?;
+        for tree in span.nodes() {
+            self.format_tree(tree, Some(root_duration), path, writer)?;
+        }
+        path.truncate(parent_len);
+
+        if !self.spans {
+            return Ok(());
+        }
+
+        Compact::format_shared(&span.shared, writer)?;
+        write!(
+            writer,
+            "{}{} [ {} | ",
+            path,
+            span.name(),
+            DurationDisplay(span.total_duration())
+        )?;
+
+        let inner_duration = span.inner_duration().as_nanos() as f64;
+        if inner_duration > 0.0 {
+            let base_duration = span.base_duration().as_nanos() as f64;
+            write!(writer, "{:.2}% / ", 100.0 * base_duration / root_duration)?;
+        }
+
+        write!(writer, "{:.2}% ]", 100.0 * total_duration / root_duration)?;
+        Compact::format_fields(span.fields(), writer)
+    }
+
+    /// Writes fields, followed by the end of the line.
+    fn format_fields(fields: &[Field], writer: &mut String) -> fmt::Result {
+        for field in fields {
+            write!(writer, " | {}: {}", field.key(), OneLine(field.value()))?;
+        }
+        writeln!(writer)
+    }
+}
+
+impl Default for Compact {
+    fn default() -> Self {
+        Compact::new()
+    }
+}
+
+impl Formatter for Compact {
+    type Error = fmt::Error;
+
+    fn fmt(&self, tree: &Tree) -> Result<String, fmt::Error> {
+        let mut writer = String::with_capacity(256);
+        self.format_tree(tree, None, &mut String::new(), &mut writer)?;
+        Ok(writer)
+    }
+}
+
+/// Escapes line breaks, so text can't span multiple lines.
+struct OneLine<'a>(&'a str);
+
+impl fmt::Display for OneLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
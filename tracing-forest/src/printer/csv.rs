@@ -0,0 +1,236 @@
+use crate::printer::Formatter;
+use crate::tree::{Event, Field, Shared, Span, Tree};
+use std::fmt::{self, Write};
+
+/// The columns of every row, in order.
+const HEADER: &str = "kind,uuid,path,depth,name,level,start,\
+                      total_nanos,base_nanos,inner_nanos,tag,message,fields\n";
+
+/// Format logs as CSV, with one row per span and optionally one per event.
+///
+/// Every row has the same columns, so the output can be loaded directly into a
+/// spreadsheet or a data frame:
+///
+/// | Column | Spans | Events |
+/// |---|---|---|
+/// | `kind` | `span` | `event` |
+/// | `uuid` | The span's UUID | The event's UUID |
+/// | `path` | The names of the span and its ancestors, joined with `/` | The path of the enclosing span |
+/// | `depth` | 0 for root spans | One more than the enclosing span |
+/// | `name` | The span's name | Empty |
+/// | `level` | The span's level | The event's level |
+/// | `start` | When the span was created, in RFC 3339 | When the event occurred |
+/// | `total_nanos`, `base_nanos`, `inner_nanos` | The span's durations | Empty |
+/// | `tag` | Empty | The event's tag, if it has one |
+/// | `message` | Empty | The event's message |
+/// | `fields` | The span's fields as a JSON object of strings | The event's fields |
+///
+/// `uuid` and `start` are empty unless the `uuid` and `chrono` features are
+/// enabled.
+///
+/// Used as a [`Formatter`], each tree is formatted as rows without a header,
+/// so that a [`Printer`] can append to a file whose first line is
+/// [`Csv::header`]. Use [`Csv::report`] to format many trees, such as those
+/// returned by [`capture`], with a header.
+///
+/// [`Printer`]: crate::Printer
+/// [`capture`]: crate::capture
+///
+/// # Examples
+///
+/// Writing the spans and events of a test to a file:
+/// ```no_run
+/// # use tracing::info;
+/// # use tracing_forest::printer::Csv;
+/// # #[tokio::main]
+/// # async fn main() -> std::io::Result<()> {
+/// let logs = tracing_forest::capture()
+///     .build()
+///     .on(async {
+///         info!("Hello, world!");
+///     })
+///     .await;
+///
+/// std::fs::write("trees.csv", Csv::new().events(true).report(&logs))?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Csv {
+    events: bool,
+}
+
+impl Csv {
+    /// Returns a new CSV formatter that only writes rows for spans.
+    pub const fn new() -> Self {
+        Csv { events: false }
+    }
+
+    /// Set whether to also write a row for each event.
+    pub fn events(self, events: bool) -> Self {
+        Csv { events }
+    }
+
+    /// Returns the header row, including the trailing newline.
+    pub const fn header() -> &'static str {
+        HEADER
+    }
+
+    /// Format many trees as CSV, starting with the header row.
+    pub fn report<'a>(&self, trees: impl IntoIterator<Item = &'a Tree>) -> String {
+        let mut writer = String::from(HEADER);
+        let mut path = String::new();
+        for tree in trees {
+            // Writing to a `String` is infallible.
+            let _ = self.format_tree(tree, &mut path, 0, &mut writer);
+        }
+        writer
+    }
+
+    fn format_tree(
+        &self,
+        tree: &Tree,
+        path: &mut String,
+        depth: usize,
+        writer: &mut String,
+    ) -> fmt::Result {
+        match tree {
+            Tree::Event(event) if self.events => Csv::format_event(event, path, depth, writer),
+            Tree::Event(_) => Ok(()),
+            Tree::Span(span) => self.format_span(span, path, depth, writer),
+        }
+    }
+
+    fn format_event(event: &Event, path: &str, depth: usize, writer: &mut String) -> fmt::Result {
+        writer.write_str("event,")?;
+        Csv::format_uuid(&event.shared, writer)?;
+        write!(writer, "{},{},,{},", Quote(path), depth, event.level())?;
+        Csv::format_start(&event.shared, writer)?;
+        writer.write_str(",,,,")?;
+        if let Some(tag) = event.tag() {
+            write!(writer, "{}", Quote(&tag.to_string()))?;
+        }
+        write!(writer, ",{},", Quote(event.message().unwrap_or_default()))?;
+        Csv::format_fields(event.fields(), writer)
+    }
+
+    fn format_span(
+        &self,
+        span: &Span,
+        path: &mut String,
+        depth: usize,
+        writer: &mut String,
+    ) -> fmt::Result {
+        let parent_len = path.len();
+        if depth > 0 {
+            path.push('/');
+        }
+        path.push_str(span.name());
+
+        writer.write_str("span,")?;
+        Csv::format_uuid(&span.shared, writer)?;
+        write!(
+            writer,
+            "{},{},{},{},",
+            Quote(path),
+            depth,
+            Quote(span.name()),
+            span.level()
+        )?;
+        Csv::format_start(&span.shared, writer)?;
+        write!(
+            writer,
+            ",{},{},{},,,",
+            span.total_duration().as_nanos(),
+            span.base_duration().as_nanos(),
+            span.inner_duration().as_nanos()
+        )?;
+        Csv::format_fields(span.fields(), writer)?;
+
+        for tree in span.nodes() {
+            self.format_tree(tree, path, depth + 1, writer)?;
+        }
+
+        path.truncate(parent_len);
+        Ok(())
+    }
+
+    fn format_uuid(_shared: &Shared, writer: &mut String) -> fmt::Result {
+        #[cfg(feature = "uuid")]
+        write!(writer, "{}", _shared.uuid)?;
+
+        writer.write_char(',')
+    }
+
+    fn format_start(_shared: &Shared, _writer: &mut String) -> fmt::Result {
+        #[cfg(feature = "chrono")]
+        _writer.write_str(&_shared.timestamp.to_rfc3339())?;
+
+        Ok(())
+    }
+
+    /// Writes fields as a JSON object, followed by the end of the row.
+    fn format_fields(fields: &[Field], writer: &mut String) -> fmt::Result {
+        let mut json = String::from("{");
+        for (n, field) in fields.iter().enumerate() {
+            if n > 0 {
+                json.push(',');
+            }
+            write!(json, "{}:{}", Json(field.key()), Json(field.value()))?;
+        }
+        json.push('}');
+
+        writeln!(writer, "{}", Quote(&json))
+    }
+}
+
+impl Formatter for Csv {
+    type Error = fmt::Error;
+
+    fn fmt(&self, tree: &Tree) -> Result<String, fmt::Error> {
+        let mut writer = String::with_capacity(256);
+        self.format_tree(tree, &mut String::new(), 0, &mut writer)?;
+        Ok(writer)
+    }
+}
+
+/// Quotes a CSV cell if it contains a delimiter, quote, or line break.
+struct Quote<'a>(&'a str);
+
+impl fmt::Display for Quote<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.0.contains([',', '"', '\n', '\r']) {
+            return f.write_str(self.0);
+        }
+
+        f.write_char('"')?;
+        for (n, part) in self.0.split('"').enumerate() {
+            if n > 0 {
+                f.write_str("\"\"")?;
+            }
+            f.write_str(part)?;
+        }
+        f.write_char('"')
+    }
+}
+
+/// Formats a string as a JSON string literal.
+struct Json<'a>(&'a str);
+
+impl fmt::Display for Json<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
@@ -0,0 +1,254 @@
+use crate::printer::DurationDisplay;
+use crate::printer::Formatter;
+use crate::tree::{Event, Field, Span, Tree};
+use crate::Tag;
+#[cfg(feature = "uuid")]
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+use tracing::Level;
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
+
+/// Format logs as a [Graphviz] DOT digraph.
+///
+/// Spans are drawn as boxes labeled with their name, duration, and percent of
+/// the root span's duration, and events are drawn as notes colored by level.
+/// Each node has an edge from the span it occurred in. The output can be
+/// rendered with `dot -Tsvg`, or embedded in documents that support Graphviz.
+///
+/// With the `uuid` feature, spans that [follow from] other spans also have a
+/// dashed edge from each of those spans that is drawn, whether it's in the same
+/// tree or in another tree drawn by [`Dot::report`]. Spans are matched by their
+/// [`id`].
+///
+/// Used as a [`Formatter`], each tree is rendered as its own digraph. Use
+/// [`Dot::report`] to render many trees, such as those returned by
+/// [`capture`], into one digraph.
+///
+/// [Graphviz]: https://graphviz.org
+/// [follow from]: tracing::Span::follows_from
+/// [`id`]: crate::tree::Span::id
+/// [`capture`]: crate::capture
+///
+/// # Examples
+///
+/// ```
+/// # use tracing::{info, info_span};
+/// # use tracing_forest::printer::{Dot, Formatter};
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), std::fmt::Error> {
+/// let logs = tracing_forest::capture()
+///     .build()
+///     .on(async {
+///         info_span!("checkout").in_scope(|| {
+///             info!("Hello, world!");
+///         });
+///     })
+///     .await;
+///
+/// let dot = Dot::new().fmt(&logs[0])?;
+/// assert!(dot.starts_with("digraph"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Dot {
+    left_to_right: bool,
+}
+
+impl Dot {
+    /// Returns a new DOT formatter that draws trees from top to bottom.
+    pub const fn new() -> Self {
+        Dot {
+            left_to_right: false,
+        }
+    }
+
+    /// Set whether trees are drawn from left to right instead of top to
+    /// bottom, which suits deep trees better.
+    pub fn left_to_right(self, left_to_right: bool) -> Self {
+        Dot { left_to_right }
+    }
+
+    /// Render many trees into a single digraph.
+    pub fn report<'a>(&self, trees: impl IntoIterator<Item = &'a Tree>) -> String {
+        let mut writer = String::with_capacity(1024);
+        // Writing to a `String` is infallible.
+        let _ = self.format_graph(trees, &mut writer);
+        writer
+    }
+
+    fn format_graph<'a>(
+        &self,
+        trees: impl IntoIterator<Item = &'a Tree>,
+        writer: &mut String,
+    ) -> fmt::Result {
+        writer.write_str("digraph forest {\n")?;
+        if self.left_to_right {
+            writer.write_str("    rankdir=LR;\n")?;
+        }
+        writer.write_str("    node [fontname=\"Helvetica\", fontsize=10];\n")?;
+        writer.write_str("    edge [color=\"#888888\"];\n")?;
+
+        let mut graph = Graph::default();
+        for tree in trees {
+            Dot::format_tree(tree, None, None, &mut graph, writer)?;
+        }
+
+        #[cfg(feature = "uuid")]
+        for (id, follows) in &graph.follows_from {
+            match graph.spans.get(follows) {
+                Some(from) if from != id => {
+                    writeln!(writer, "    n{} -> n{} [style=dashed];", from, id)?
+                }
+                _ => {}
+            }
+        }
+
+        writer.write_str("}\n")
+    }
+
+    fn format_tree(
+        tree: &Tree,
+        parent: Option<usize>,
+        root: Option<f64>,
+        graph: &mut Graph,
+        writer: &mut String,
+    ) -> fmt::Result {
+        let id = graph.next_id;
+        graph.next_id += 1;
+
+        match tree {
+            Tree::Event(event) => Dot::format_event(event, id, writer)?,
+            Tree::Span(span) => Dot::format_span(span, id, root, graph, writer)?,
+        }
+
+        if let Some(parent) = parent {
+            writeln!(writer, "    n{} -> n{};", parent, id)?;
+        }
+        Ok(())
+    }
+
+    fn format_event(event: &Event, id: usize, writer: &mut String) -> fmt::Result {
+        let tag = event.tag().unwrap_or_else(|| Tag::from(event.level()));
+
+        let mut label = format!("{} [{}]", tag.icon(), tag);
+        if let Some(message) = event.message() {
+            write!(label, ": {}", message)?;
+        }
+        Dot::format_fields(event.fields(), &mut label)?;
+
+        writeln!(
+            writer,
+            "    n{} [shape=note, style=filled, fillcolor=\"{}\", label={}];",
+            id,
+            fill_color(event.level()),
+            Quote(&label)
+        )
+    }
+
+    fn format_span(
+        span: &Span,
+        id: usize,
+        root: Option<f64>,
+        graph: &mut Graph,
+        writer: &mut String,
+    ) -> fmt::Result {
+        #[cfg(feature = "uuid")]
+        {
+            // Spans deserialized without IDs can't be told apart.
+            if !span.id().is_nil() {
+                graph.spans.insert(span.id(), id);
+            }
+            for &follows in span.follows_from() {
+                graph.follows_from.push((id, follows));
+            }
+        }
+
+        let total_duration = span.total_duration().as_nanos() as f64;
+        let root_duration = root.unwrap_or(total_duration);
+        let percent = if root_duration > 0.0 {
+            100.0 * total_duration / root_duration
+        } else {
+            100.0
+        };
+
+        let mut label = format!(
+            "{}\n{} | {:.2}%",
+            span.name(),
+            DurationDisplay(span.total_duration()),
+            percent
+        );
+        Dot::format_fields(span.fields(), &mut label)?;
+
+        writeln!(
+            writer,
+            "    n{} [shape=box, style=\"rounded\", label={}];",
+            id,
+            Quote(&label)
+        )?;
+
+        for tree in span.nodes() {
+            Dot::format_tree(tree, Some(id), Some(root_duration), graph, writer)?;
+        }
+        Ok(())
+    }
+
+    fn format_fields(fields: &[Field], label: &mut String) -> fmt::Result {
+        for field in fields {
+            write!(label, "\n{}: {}", field.key(), field.value())?;
+        }
+        Ok(())
+    }
+}
+
+impl Formatter for Dot {
+    type Error = fmt::Error;
+
+    fn fmt(&self, tree: &Tree) -> Result<String, fmt::Error> {
+        let mut writer = String::with_capacity(1024);
+        self.format_graph(Some(tree), &mut writer)?;
+        Ok(writer)
+    }
+}
+
+/// The nodes drawn so far in a digraph.
+#[derive(Default)]
+struct Graph {
+    next_id: usize,
+    /// The span drawn with each ID.
+    #[cfg(feature = "uuid")]
+    spans: HashMap<Uuid, usize>,
+    /// Each span that follows from others, with the ID of one it follows from.
+    #[cfg(feature = "uuid")]
+    follows_from: Vec<(usize, Uuid)>,
+}
+
+fn fill_color(level: Level) -> &'static str {
+    match level {
+        Level::TRACE => "#eadcf8",
+        Level::DEBUG => "#dbeafe",
+        Level::INFO => "#dcfce7",
+        Level::WARN => "#fef3c7",
+        Level::ERROR => "#fee2e2",
+    }
+}
+
+/// Formats text as a quoted DOT string, with line breaks as `\n`.
+struct Quote<'a>(&'a str);
+
+impl fmt::Display for Quote<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => {}
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
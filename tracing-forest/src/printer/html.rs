@@ -0,0 +1,304 @@
+use crate::printer::DurationDisplay;
+use crate::printer::Formatter;
+use crate::tree::{Event, Shared, Span, Tree};
+use crate::Tag;
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+
+/// Format logs as a self-contained HTML report.
+///
+/// The report is a single static file with inline styles and scripts, which
+/// makes it suitable as a CI artifact. Spans can be collapsed and expanded,
+/// and show a bar of their duration relative to the root span. Levels are
+/// colored, events show the icon of their [`Tag`], and a search box hides
+/// everything that doesn't match.
+///
+/// Used as a [`Formatter`], each tree is rendered as its own document. Use
+/// [`Html::report`] to render many trees, such as those returned by
+/// [`capture`], into one document.
+///
+/// # Examples
+///
+/// Writing the logs of a test to a file:
+/// ```no_run
+/// # use tracing::info;
+/// # use tracing_forest::printer::Html;
+/// # #[tokio::main]
+/// # async fn main() -> std::io::Result<()> {
+/// let logs = tracing_forest::capture()
+///     .build()
+///     .on(async {
+///         info!("Hello, world!");
+///     })
+///     .await;
+///
+/// let report = Html::new().title("Hello world").report(&logs);
+/// std::fs::write("report.html", report)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`capture`]: crate::capture
+#[derive(Clone, Debug)]
+pub struct Html {
+    title: Cow<'static, str>,
+    collapsed: bool,
+}
+
+impl Html {
+    /// Returns a new HTML formatter titled "tracing-forest report", with every
+    /// span expanded.
+    pub const fn new() -> Self {
+        Html {
+            title: Cow::Borrowed("tracing-forest report"),
+            collapsed: false,
+        }
+    }
+
+    /// Set the title of the report.
+    pub fn title(self, title: impl Into<Cow<'static, str>>) -> Self {
+        Html {
+            title: title.into(),
+            ..self
+        }
+    }
+
+    /// Set whether spans start collapsed.
+    ///
+    /// Spans containing a search match are expanded regardless.
+    pub fn collapsed(self, collapsed: bool) -> Self {
+        Html { collapsed, ..self }
+    }
+
+    /// Render many trees into a single HTML document.
+    pub fn report<'a>(&self, trees: impl IntoIterator<Item = &'a Tree>) -> String {
+        let mut writer = String::with_capacity(4096);
+        // Writing to a `String` is infallible.
+        let _ = self.format_report(trees, &mut writer);
+        writer
+    }
+
+    fn format_report<'a>(
+        &self,
+        trees: impl IntoIterator<Item = &'a Tree>,
+        writer: &mut String,
+    ) -> fmt::Result {
+        let title = Escape(&self.title);
+
+        writer.write_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n")?;
+        writer.write_str("<meta charset=\"utf-8\">\n")?;
+        writeln!(writer, "<title>{}</title>", title)?;
+        writeln!(writer, "<style>{}</style>", STYLE)?;
+        writer.write_str("</head>\n<body>\n<header>\n")?;
+        writeln!(writer, "<h1>{}</h1>", title)?;
+        writer.write_str(
+            "<input id=\"search\" type=\"search\" placeholder=\"Search\" autofocus>\n\
+             <span id=\"matches\"></span>\n</header>\n<main>\n",
+        )?;
+
+        for tree in trees {
+            self.format_tree(tree, None, writer)?;
+        }
+
+        writer.write_str("</main>\n")?;
+        writeln!(writer, "<script>{}</script>", SCRIPT)?;
+        writer.write_str("</body>\n</html>\n")
+    }
+
+    fn format_tree(&self, tree: &Tree, root: Option<f64>, writer: &mut String) -> fmt::Result {
+        match tree {
+            Tree::Event(event) => Html::format_event(event, writer),
+            Tree::Span(span) => self.format_span(span, root, writer),
+        }
+    }
+
+    fn format_event(event: &Event, writer: &mut String) -> fmt::Result {
+        let tag = event.tag().unwrap_or_else(|| Tag::from(event.level()));
+
+        Html::open_node("div", "event", &event.shared, writer)?;
+        Html::format_level(&event.shared, writer)?;
+        write!(
+            writer,
+            "<span class=\"icon\">{}</span> <span class=\"tag\">[{}]</span> ",
+            Escape(&tag.icon().to_string()),
+            Escape(&tag.to_string())
+        )?;
+
+        if let Some(message) = event.message() {
+            write!(writer, "<span class=\"message\">{}</span>", Escape(message))?;
+        }
+
+        Html::format_fields(&event.shared, writer)?;
+        writer.write_str("</div>\n")
+    }
+
+    fn format_span(&self, span: &Span, root: Option<f64>, writer: &mut String) -> fmt::Result {
+        let total_duration = span.total_duration().as_nanos() as f64;
+        let root_duration = root.unwrap_or(total_duration);
+        let percent = if root_duration > 0.0 {
+            100.0 * total_duration / root_duration
+        } else {
+            100.0
+        };
+
+        let element = if self.collapsed {
+            "details"
+        } else {
+            "details open"
+        };
+        Html::open_node(element, "span", &span.shared, writer)?;
+        writer.write_str("<summary>")?;
+        Html::format_level(&span.shared, writer)?;
+        write!(
+            writer,
+            "<span class=\"name\">{}</span> \
+             <span class=\"duration\">{}</span> \
+             <span class=\"bar\" title=\"{:.2}% of root\"><span style=\"width: {:.2}%\"></span></span>",
+            Escape(span.name()),
+            DurationDisplay(span.total_duration()),
+            percent,
+            percent,
+        )?;
+        Html::format_fields(&span.shared, writer)?;
+        writer.write_str("</summary>\n")?;
+
+        for tree in span.nodes() {
+            self.format_tree(tree, Some(root_duration), writer)?;
+        }
+
+        writer.write_str("</details>\n")
+    }
+
+    /// Writes the opening tag of a node, classed by its kind and level.
+    fn open_node(element: &str, class: &str, shared: &Shared, writer: &mut String) -> fmt::Result {
+        write!(
+            writer,
+            "<{} class=\"node {} {}\"",
+            element,
+            class,
+            shared.level.as_str().to_ascii_lowercase()
+        )?;
+
+        #[cfg(feature = "uuid")]
+        write!(writer, " data-uuid=\"{}\"", shared.uuid)?;
+
+        #[cfg(feature = "chrono")]
+        write!(writer, " title=\"{}\"", shared.timestamp.to_rfc3339())?;
+
+        writer.write_char('>')
+    }
+
+    fn format_level(shared: &Shared, writer: &mut String) -> fmt::Result {
+        write!(writer, "<span class=\"level\">{}</span> ", shared.level)
+    }
+
+    fn format_fields(shared: &Shared, writer: &mut String) -> fmt::Result {
+        for field in shared.fields.iter() {
+            write!(
+                writer,
+                " <span class=\"field\"><span class=\"key\">{}</span>: {}</span>",
+                Escape(field.key()),
+                Escape(field.value())
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for Html {
+    fn default() -> Self {
+        Html::new()
+    }
+}
+
+impl Formatter for Html {
+    type Error = fmt::Error;
+
+    fn fmt(&self, tree: &Tree) -> Result<String, fmt::Error> {
+        let mut writer = String::with_capacity(4096);
+        self.format_report(Some(tree), &mut writer)?;
+        Ok(writer)
+    }
+}
+
+/// Escapes text for use in HTML elements and quoted attributes.
+struct Escape<'a>(&'a str);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rest = self.0;
+        while let Some(index) = rest.find(['&', '<', '>', '"', '\'']) {
+            f.write_str(&rest[..index])?;
+            f.write_str(match rest.as_bytes()[index] {
+                b'&' => "&amp;",
+                b'<' => "&lt;",
+                b'>' => "&gt;",
+                b'"' => "&quot;",
+                _ => "&#39;",
+            })?;
+            rest = &rest[index + 1..];
+        }
+        f.write_str(rest)
+    }
+}
+
+const STYLE: &str = r#"
+body { font: 13px/1.5 ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; margin: 0; color: #1f2328; background: #fff; }
+header { position: sticky; top: 0; display: flex; gap: 1em; align-items: center; padding: .5em 1em; background: #f6f8fa; border-bottom: 1px solid #d0d7de; }
+h1 { font-size: 1.2em; margin: 0; }
+#search { flex: 0 1 30em; font: inherit; padding: .2em .5em; }
+#matches { color: #656d76; }
+main { padding: .5em 1em; }
+.node { white-space: nowrap; }
+details > .node, details > details { margin-left: 1.5em; }
+summary { cursor: pointer; }
+.hidden { display: none; }
+.level { display: inline-block; width: 5.5em; font-weight: bold; }
+.trace > .level, .trace > summary > .level { color: #8250df; }
+.debug > .level, .debug > summary > .level { color: #0969da; }
+.info > .level, .info > summary > .level { color: #1a7f37; }
+.warn > .level, .warn > summary > .level { color: #bc4c00; }
+.error > .level, .error > summary > .level { color: #cf222e; }
+.name { font-weight: bold; }
+.duration { color: #656d76; }
+.bar { display: inline-block; width: 10em; height: .7em; margin: 0 .5em; background: #eaeef2; vertical-align: middle; }
+.bar > span { display: block; height: 100%; background: #54aeff; }
+.tag { color: #656d76; }
+.field { color: #656d76; margin-left: .5em; }
+.key { color: #953800; }
+.match > summary > .name, .match > .message { background: #fff8c5; }
+"#;
+
+const SCRIPT: &str = r#"
+const search = document.getElementById("search");
+const matches = document.getElementById("matches");
+
+function own(node) {
+  return node.tagName === "DETAILS" ? node.querySelector(":scope > summary").textContent : node.textContent;
+}
+
+function filter(node, query) {
+  let visible = own(node).toLowerCase().includes(query);
+  node.classList.toggle("match", query !== "" && visible);
+  let count = query !== "" && visible ? 1 : 0;
+  if (node.tagName === "DETAILS") {
+    let child = false;
+    for (const c of node.querySelectorAll(":scope > .node")) {
+      const [shown, n] = filter(c, query);
+      child = child || shown;
+      count += n;
+    }
+    if (query !== "" && child) node.open = true;
+    visible = visible || child;
+  }
+  node.classList.toggle("hidden", !visible);
+  return [visible, count];
+}
+
+search.addEventListener("input", () => {
+  const query = search.value.trim().toLowerCase();
+  let count = 0;
+  for (const node of document.querySelectorAll("main > .node")) count += filter(node, query)[1];
+  matches.textContent = query === "" ? "" : count + (count === 1 ? " match" : " matches");
+});
+"#;
@@ -0,0 +1,79 @@
+use crate::printer::Formatter;
+use crate::tree::Tree;
+use crate::Tag;
+use std::fmt::{self, Write};
+
+/// Format immediate events as single lines, marked as `IMMEDIATE`.
+///
+/// This is the default formatter for events with `immediate = true`. Each tree
+/// is expected to be an event nested inside the spans it occurred in, as
+/// passed to the [immediate processor], and is written with the following
+/// format:
+/// ```txt
+/// <UUID> <TIMESTAMP> <LEVEL> <ICON> IMMEDIATE <ICON> <ROOT> > ... > <LEAF> > <MESSAGE> | <KEY>: <VALUE>
+/// ```
+/// The `UUID` is only written if the event occurred in a span and the `uuid`
+/// feature is enabled, and the `TIMESTAMP` is only written if the `chrono`
+/// feature is enabled.
+///
+/// [immediate processor]: crate::ForestLayer::immediate
+///
+/// # Examples
+///
+/// ```log
+/// INFO     ｉ IMMEDIATE ｉ my_span > third, but immediately
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Immediate;
+
+impl Formatter for Immediate {
+    type Error = fmt::Error;
+
+    fn fmt(&self, tree: &Tree) -> Result<String, fmt::Error> {
+        let mut writer = String::with_capacity(256);
+        let mut names = Vec::new();
+        let mut tree = tree;
+
+        // Descend to the event, collecting the names of the spans around it.
+        let event = loop {
+            match tree {
+                Tree::Event(event) => break event,
+                Tree::Span(span) => match span.nodes().first() {
+                    Some(node) => {
+                        names.push(span.name());
+                        tree = node;
+                    }
+                    None => return Ok(writer),
+                },
+            }
+        };
+
+        #[cfg(feature = "uuid")]
+        if !names.is_empty() {
+            write!(writer, "{} ", event.uuid())?;
+        }
+
+        #[cfg(feature = "chrono")]
+        write!(writer, "{} ", event.timestamp().to_rfc3339())?;
+
+        write!(writer, "{:<8} ", event.level())?;
+
+        let tag = event.tag().unwrap_or_else(|| Tag::from(event.level()));
+        write!(writer, "{icon} IMMEDIATE {icon} ", icon = tag.icon())?;
+
+        for name in names {
+            write!(writer, "{} > ", name)?;
+        }
+
+        if let Some(message) = event.message() {
+            writer.write_str(message)?;
+        }
+
+        for field in event.fields() {
+            write!(writer, " | {}: {}", field.key(), field.value())?;
+        }
+
+        writeln!(writer)?;
+        Ok(writer)
+    }
+}
@@ -1,12 +1,29 @@
 //! Utilities for formatting and writing trace trees.
+use crate::cfg_rolling_file;
+use crate::processor::batch::{self, BatchProcessor};
 use crate::processor::{self, Processor};
 use crate::tree::Tree;
 use std::error::Error;
 use std::io::{self, Write};
 use tracing_subscriber::fmt::MakeWriter;
 
+mod compact;
+mod csv;
+mod dot;
+mod html;
+mod immediate;
 mod pretty;
-pub use pretty::Pretty;
+pub use compact::Compact;
+pub use csv::Csv;
+pub use dot::Dot;
+pub use html::Html;
+pub use immediate::Immediate;
+pub use pretty::{DurationDisplay, Pretty, PrettyConfig};
+
+cfg_rolling_file! {
+    mod rolling;
+    pub use rolling::{Builder as RollingFileBuilder, RollingFile, RollingWriter, Rotation};
+}
 
 /// Format a [`Tree`] into a `String`.
 ///
@@ -108,6 +125,14 @@ impl<'a> MakeWriter<'a> for MakeStderr {
 /// A [`Processor`] that pretty-prints to stdout.
 pub type PrettyPrinter = Printer<Pretty, MakeStdout>;
 
+/// The default [`Processor`] for events with `immediate = true`, which writes
+/// them to stderr with the [`Immediate`] formatter.
+///
+/// See [`ForestLayer::immediate`] for details.
+///
+/// [`ForestLayer::immediate`]: crate::ForestLayer::immediate
+pub type ImmediatePrinter = Printer<Immediate, MakeStderr>;
+
 impl PrettyPrinter {
     /// Returns a new [`PrettyPrinter`] that pretty-prints to stdout.
     ///
@@ -174,6 +199,27 @@ where
     }
 }
 
+impl<F, W> BatchProcessor for Printer<F, W>
+where
+    F: 'static + Formatter,
+    W: 'static + for<'a> MakeWriter<'a>,
+{
+    fn process_batch(&self, trees: Vec<Tree>) -> batch::Result {
+        let mut string = String::new();
+        for tree in trees.iter() {
+            match self.formatter.fmt(tree) {
+                Ok(s) => string.push_str(&s),
+                Err(e) => return Err(batch::error(trees, e.into())),
+            }
+        }
+
+        match self.make_writer.make_writer().write_all(string.as_bytes()) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(batch::error(trees, e.into())),
+        }
+    }
+}
+
 /// A [`Processor`] that captures logs during tests and allows them to be presented
 /// when --nocapture is used.
 #[derive(Clone, Debug)]
@@ -189,6 +235,12 @@ impl TestCapturePrinter<Pretty> {
     }
 }
 
+impl Default for TestCapturePrinter<Pretty> {
+    fn default() -> Self {
+        TestCapturePrinter::new()
+    }
+}
+
 impl<F> Processor for TestCapturePrinter<F>
 where
     F: 'static + Formatter,
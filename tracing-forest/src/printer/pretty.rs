@@ -1,7 +1,10 @@
+use crate::cfg_cpu_time;
+use crate::collapse::{Collapse, Collapsed};
 use crate::printer::Formatter;
 use crate::tree::{Event, Shared, Span, Tree};
 use crate::Tag;
 use std::fmt::{self, Write};
+use std::time::Duration;
 
 #[cfg(feature = "smallvec")]
 type IndentVec = smallvec::SmallVec<[Indent; 32]>;
@@ -22,12 +25,12 @@ use tracing::Level;
 /// <NAME> [ <DURATION> | <BODY> / <ROOT> ]
 /// ```
 /// * `DURATION` represents the total time the span was entered for. If the span
-/// was used to instrument a `Future` that sleeps, then that time won't be counted
-/// since the `Future` won't be polled during that time, and so the span won't enter.
+///   was used to instrument a `Future` that sleeps, then that time won't be counted
+///   since the `Future` won't be polled during that time, and so the span won't enter.
 /// * `BODY` represents the percent time the span is entered relative to the root
-/// span, *excluding* time that any child spans are entered.
+///   span, *excluding* time that any child spans are entered.
 /// * `ROOT` represents the percent time the span is entered relative to the root
-/// span, *including* time that any child spans are entered.
+///   span, *including* time that any child spans are entered.
 ///
 /// As a mental model, look at `ROOT` to quickly narrow down which branches are
 /// costly, and look at `BASE` to pinpoint exactly which spans are expensive.
@@ -35,6 +38,13 @@ use tracing::Level;
 /// Spans without any child spans would have the same `BASE` and `ROOT`, so the
 /// redundency is omitted.
 ///
+/// Spans that were open but not entered for longer than they were entered,
+/// like those instrumenting a `Future` that spends most of its life waiting,
+/// also show their idle time:
+/// ```txt
+/// <NAME> [ <DURATION> busy, <IDLE> idle | <BODY> / <ROOT> ]
+/// ```
+///
 /// # Examples
 ///
 /// An arbitrarily complex example:
@@ -58,23 +68,112 @@ use tracing::Level;
 /// WARN     │     ┕━ 🚧 [filter.warn]: Some filter warning
 /// TRACE    ┕━ 📍 [trace]: Finished!
 /// ```
+///
+/// Use [`PrettyConfig`] to configure the output.
 #[derive(Debug)]
 pub struct Pretty;
 
 impl Formatter for Pretty {
     type Error = fmt::Error;
 
+    fn fmt(&self, tree: &Tree) -> Result<String, fmt::Error> {
+        PrettyConfig::new().fmt(tree)
+    }
+}
+
+impl Pretty {
+    /// Returns a formatter that merges repeated events and spans before
+    /// pretty-printing.
+    ///
+    /// See [`Collapse`] for details.
+    pub const fn collapsed(self) -> Collapsed<Pretty> {
+        Collapse::new().wrap(self)
+    }
+}
+
+/// A configurable version of the [`Pretty`] formatter.
+///
+/// # Flagging slow polls
+///
+/// Each time a span is entered counts as one entry. For a span instrumenting a
+/// `Future`, this is one poll. A poll that takes a long time usually means
+/// blocking code is running inside an async task, stalling the other tasks on
+/// its thread. With [`slow_poll`], spans whose longest entry exceeded a
+/// threshold are flagged with it:
+/// ```txt
+/// INFO     handle_request [ 52.1ms | 100.00% ] ⚠ slow poll: 50.3ms
+/// ```
+///
+/// [`slow_poll`]: PrettyConfig::slow_poll
+///
+/// # Showing CPU time
+///
+/// With the `cpu-time` feature on Linux, [`cpu_time`] also shows the CPU time
+/// each span's thread used while it was entered:
+/// ```txt
+/// INFO     compute [ 30.1ms, 29.8ms cpu | 100.00% ]
+/// ```
+/// CPU time much lower than the duration means the span mostly waited, like
+/// on I/O.
+///
+/// [`cpu_time`]: PrettyConfig::cpu_time
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use tracing_forest::printer::{PrettyConfig, Printer};
+///
+/// let printer = Printer::new().formatter(PrettyConfig::new().slow_poll(Duration::from_millis(10)));
+/// # let _ = printer;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PrettyConfig {
+    slow_poll: Option<Duration>,
+    #[cfg(all(feature = "cpu-time", target_os = "linux"))]
+    cpu_time: bool,
+}
+
+impl Formatter for PrettyConfig {
+    type Error = fmt::Error;
+
     fn fmt(&self, tree: &Tree) -> Result<String, fmt::Error> {
         let mut writer = String::with_capacity(256);
 
-        Pretty::format_tree(tree, None, &mut IndentVec::new(), &mut writer)?;
+        self.format_tree(tree, None, &mut IndentVec::new(), &mut writer)?;
 
         Ok(writer)
     }
 }
 
-impl Pretty {
+impl PrettyConfig {
+    /// Returns a new `PrettyConfig`, which formats the same as [`Pretty`].
+    pub const fn new() -> Self {
+        PrettyConfig {
+            slow_poll: None,
+            #[cfg(all(feature = "cpu-time", target_os = "linux"))]
+            cpu_time: false,
+        }
+    }
+
+    /// Flag spans that were entered for longer than `threshold` at once.
+    pub const fn slow_poll(mut self, threshold: Duration) -> Self {
+        self.slow_poll = Some(threshold);
+        self
+    }
+
+    cfg_cpu_time! {
+        /// Show the CPU time used by each span's thread while it was entered.
+        ///
+        /// This is off by default.
+        pub const fn cpu_time(mut self, show: bool) -> Self {
+            self.cpu_time = show;
+            self
+        }
+    }
+
     fn format_tree(
+        &self,
         tree: &Tree,
         duration_root: Option<f64>,
         indent: &mut IndentVec,
@@ -82,14 +181,14 @@ impl Pretty {
     ) -> fmt::Result {
         match tree {
             Tree::Event(event) => {
-                Pretty::format_shared(&event.shared, writer)?;
-                Pretty::format_indent(indent, writer)?;
-                Pretty::format_event(event, writer)
+                Self::format_shared(&event.shared, writer)?;
+                Self::format_indent(indent, writer)?;
+                Self::format_event(event, writer)
             }
             Tree::Span(span) => {
-                Pretty::format_shared(&span.shared, writer)?;
-                Pretty::format_indent(indent, writer)?;
-                Pretty::format_span(span, duration_root, indent, writer)
+                Self::format_shared(&span.shared, writer)?;
+                Self::format_indent(indent, writer)?;
+                self.format_span(span, duration_root, indent, writer)
             }
         }
     }
@@ -132,6 +231,7 @@ impl Pretty {
     }
 
     fn format_span(
+        &self,
         span: &Span,
         duration_root: Option<f64>,
         indent: &mut IndentVec,
@@ -144,11 +244,23 @@ impl Pretty {
 
         write!(
             writer,
-            "{} [ {} | ",
+            "{} [ {}",
             span.name(),
-            DurationDisplay(total_duration)
+            DurationDisplay(span.total_duration())
         )?;
 
+        let idle_duration = span.idle_duration();
+        if idle_duration > span.total_duration() {
+            write!(writer, " busy, {} idle", DurationDisplay(idle_duration))?;
+        }
+
+        #[cfg(all(feature = "cpu-time", target_os = "linux"))]
+        if self.cpu_time {
+            write!(writer, ", {} cpu", DurationDisplay(span.cpu_time()))?;
+        }
+
+        writer.write_str(" | ")?;
+
         if inner_duration > 0.0 {
             let base_duration = span.base_duration().as_nanos() as f64;
             let percent_base_of_root_duration = 100.0 * base_duration / root_duration;
@@ -157,6 +269,17 @@ impl Pretty {
 
         write!(writer, "{:.2}% ]", percent_total_of_root_duration)?;
 
+        if let Some(threshold) = self.slow_poll {
+            let max_entry_duration = span.max_entry_duration();
+            if max_entry_duration > threshold {
+                write!(
+                    writer,
+                    " ⚠ slow poll: {}",
+                    DurationDisplay(max_entry_duration)
+                )?;
+            }
+        }
+
         for (n, field) in span.shared.fields.iter().enumerate() {
             write!(
                 writer,
@@ -181,13 +304,13 @@ impl Pretty {
                 if let Some(edge) = indent.last_mut() {
                     *edge = Indent::Fork;
                 }
-                Pretty::format_tree(tree, Some(root_duration), indent, writer)?;
+                self.format_tree(tree, Some(root_duration), indent, writer)?;
             }
 
             if let Some(edge) = indent.last_mut() {
                 *edge = Indent::Turn;
             }
-            Pretty::format_tree(last, Some(root_duration), indent, writer)?;
+            self.format_tree(last, Some(root_duration), indent, writer)?;
 
             indent.pop();
         }
@@ -214,12 +337,25 @@ impl Indent {
     }
 }
 
-struct DurationDisplay(f64);
+/// Displays a [`Duration`] with three significant figures, like `1.23ms`, as
+/// the [`Pretty`] formatter does.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use tracing_forest::printer::DurationDisplay;
+///
+/// assert_eq!(DurationDisplay(Duration::from_micros(1234)).to_string(), "1.23ms");
+/// assert_eq!(DurationDisplay(Duration::from_nanos(45_600)).to_string(), "45.6µs");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct DurationDisplay(pub Duration);
 
 // Taken from chrono
 impl fmt::Display for DurationDisplay {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut t = self.0;
+        let mut t = self.0.as_nanos() as f64;
         for unit in ["ns", "µs", "ms", "s"] {
             if t < 10.0 {
                 return write!(f, "{:.2}{}", t, unit);
@@ -0,0 +1,492 @@
+use chrono::{DateTime, Utc};
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::SystemTime;
+use tracing_subscriber::fmt::MakeWriter;
+
+const DATE: &str = "{date}";
+const INDEX: &str = "{index}";
+#[cfg(feature = "gzip")]
+const GZ: &str = ".gz";
+
+/// How often a [`RollingFile`] starts a new file, regardless of its size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    /// Never rotate based on time.
+    Never,
+
+    /// Rotate at the start of every hour (UTC).
+    Hourly,
+
+    /// Rotate at the start of every day (UTC).
+    Daily,
+}
+
+impl Rotation {
+    fn date_format(self) -> &'static str {
+        match self {
+            Rotation::Hourly => "%Y-%m-%d-%H",
+            Rotation::Never | Rotation::Daily => "%Y-%m-%d",
+        }
+    }
+}
+
+/// A [`MakeWriter`] that writes to files in a directory, rotating by size and/or time.
+///
+/// File names are generated from a pattern, where `{date}` is replaced with
+/// the UTC date (and hour, if rotating hourly) that the file was started, and
+/// `{index}` is replaced with the number of size-based rotations within that
+/// period. The default pattern is `forest.{date}.{index}.log`.
+///
+/// A file is rotated before a tree is written to it, so files may exceed the
+/// [maximum size][max_bytes] by at most one tree. Rotated files can optionally
+/// be [gzip-compressed][compress], and the oldest files are deleted once there
+/// are more than the [retention count][max_files]. Compression is done by the
+/// writer that rotated the file, without blocking other writers.
+///
+/// If rotating fails, the error is returned by the next write so that the
+/// [`Printer`] can defer to a fallback processor.
+///
+/// [max_bytes]: Builder::max_bytes
+/// [compress]: Builder::compress
+/// [max_files]: Builder::max_files
+/// [`Printer`]: crate::printer::Printer
+///
+/// # Examples
+///
+/// Writing to daily files of at most 10 MiB, keeping the last 7 rotated files.
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> std::io::Result<()> {
+/// use tracing_forest::printer::{RollingFile, Rotation};
+/// use tracing_forest::traits::*;
+///
+/// let rolling = RollingFile::builder("logs")
+///     .pattern("my-service.{date}.{index}.log")
+///     .rotation(Rotation::Daily)
+///     .max_bytes(10 << 20)
+///     .max_files(7)
+///     .build()?;
+///
+/// tracing_forest::worker_task()
+///     .map_receiver(|printer| printer.writer(rolling).or_stderr())
+///     .build()
+///     .on(async {
+///         // ...
+///     })
+///     .await;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RollingFile {
+    state: Mutex<State>,
+}
+
+/// Incrementally construct a [`RollingFile`].
+///
+/// See [`RollingFile::builder`] for more details.
+#[derive(Clone, Debug)]
+pub struct Builder {
+    config: Config,
+}
+
+#[derive(Clone, Debug)]
+struct Config {
+    directory: PathBuf,
+    pattern: String,
+    rotation: Rotation,
+    max_bytes: Option<u64>,
+    max_files: Option<usize>,
+    #[cfg(feature = "gzip")]
+    compress: bool,
+    clock: fn() -> DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct State {
+    config: Config,
+    file: File,
+    path: PathBuf,
+    date: String,
+    index: usize,
+    size: u64,
+    /// Rotated files that haven't been compressed yet.
+    #[cfg(feature = "gzip")]
+    uncompressed: Vec<PathBuf>,
+}
+
+/// The [`io::Write`] type returned by [`RollingFile`].
+#[derive(Debug)]
+pub struct RollingWriter<'a> {
+    state: MutexGuard<'a, State>,
+    error: Option<io::Error>,
+}
+
+impl RollingFile {
+    /// Begin configuring a `RollingFile` that writes to files in `directory`.
+    ///
+    /// By default, files are never rotated and are never deleted.
+    pub fn builder(directory: impl AsRef<Path>) -> Builder {
+        Builder {
+            config: Config {
+                directory: directory.as_ref().to_path_buf(),
+                pattern: format!("forest.{}.{}.log", DATE, INDEX),
+                rotation: Rotation::Never,
+                max_bytes: None,
+                max_files: None,
+                #[cfg(feature = "gzip")]
+                compress: false,
+                clock: Utc::now,
+            },
+        }
+    }
+
+    /// Returns the path of the file currently being written to.
+    pub fn current_path(&self) -> PathBuf {
+        self.lock().path.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Compresses the files rotated so far without holding the lock, keeping
+    /// the first error.
+    #[cfg(feature = "gzip")]
+    fn compress_rotated<'a>(
+        &'a self,
+        mut state: MutexGuard<'a, State>,
+        mut error: Option<io::Error>,
+    ) -> (MutexGuard<'a, State>, Option<io::Error>) {
+        if state.uncompressed.is_empty() {
+            return (state, error);
+        }
+
+        let uncompressed = mem::take(&mut state.uncompressed);
+        drop(state);
+        for path in &uncompressed {
+            if let Err(compress_error) = compress(path) {
+                error.get_or_insert(compress_error);
+            }
+        }
+
+        (self.lock(), error)
+    }
+}
+
+impl Builder {
+    /// Set the file name pattern.
+    ///
+    /// `{date}` and `{index}` are replaced with the period and the size-based
+    /// rotation count. The pattern must contain `{date}` if files are rotated
+    /// based on time, and `{index}` if they're rotated based on size, so that
+    /// each file has its own name.
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.config.pattern = pattern.into();
+        self
+    }
+
+    /// Set how often files are rotated based on time.
+    pub fn rotation(mut self, rotation: Rotation) -> Self {
+        self.config.rotation = rotation;
+        self
+    }
+
+    /// Set the size in bytes after which a file is rotated.
+    pub fn max_bytes(mut self, max_bytes: u64) -> Self {
+        self.config.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Set the number of rotated files to keep, excluding the current file.
+    ///
+    /// Once exceeded, the oldest files matching the pattern are deleted.
+    pub fn max_files(mut self, max_files: usize) -> Self {
+        self.config.max_files = Some(max_files);
+        self
+    }
+
+    /// Set whether rotated files are gzip-compressed.
+    ///
+    /// Compressed files have `.gz` appended to their names.
+    #[cfg(feature = "gzip")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+    pub fn compress(mut self, compress: bool) -> Self {
+        self.config.compress = compress;
+        self
+    }
+
+    /// Set the function used to get the current time, which decides when
+    /// files are rotated based on time.
+    ///
+    /// This is [`Utc::now`] by default, and is mostly useful for testing.
+    pub fn clock(mut self, clock: fn() -> DateTime<Utc>) -> Self {
+        self.config.clock = clock;
+        self
+    }
+
+    /// Complete the [`RollingFile`], creating the directory and opening the
+    /// first file.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the directory cannot be created or
+    /// the file cannot be opened, or an error of kind
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) if the [pattern] is
+    /// missing `{date}` while rotating based on time, or `{index}` while
+    /// rotating based on size.
+    ///
+    /// [pattern]: Builder::pattern
+    pub fn build(self) -> io::Result<RollingFile> {
+        let config = self.config;
+        if config.rotation != Rotation::Never && !config.pattern.contains(DATE) {
+            return Err(invalid_pattern(DATE, "rotating based on time"));
+        }
+        if config.max_bytes.is_some() && !config.pattern.contains(INDEX) {
+            return Err(invalid_pattern(INDEX, "rotating based on size"));
+        }
+
+        fs::create_dir_all(&config.directory)?;
+
+        let date = config.date();
+
+        // Resume from the latest file of this period if there is one.
+        let mut index = 0;
+        while config.exists(&date, index + 1) {
+            index += 1;
+        }
+        if config.is_compressed(&date, index) {
+            index += 1;
+        }
+
+        let path = config.path(&date, index);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+
+        let mut state = State {
+            config,
+            file,
+            path,
+            date,
+            index,
+            size,
+            #[cfg(feature = "gzip")]
+            uncompressed: Vec::new(),
+        };
+
+        if state.is_full() {
+            state.rotate(state.date.clone(), state.index + 1)?;
+        }
+
+        #[cfg(feature = "gzip")]
+        for path in mem::take(&mut state.uncompressed) {
+            compress(&path)?;
+        }
+
+        Ok(RollingFile {
+            state: Mutex::new(state),
+        })
+    }
+}
+
+fn invalid_pattern(placeholder: &str, reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("the file name pattern must contain `{}` when {}", placeholder, reason),
+    )
+}
+
+impl Config {
+    /// Returns the current period, formatted for file names.
+    fn date(&self) -> String {
+        (self.clock)()
+            .format(self.rotation.date_format())
+            .to_string()
+    }
+
+    fn name(&self, date: &str, index: usize) -> String {
+        self.pattern
+            .replace(DATE, date)
+            .replace(INDEX, &index.to_string())
+    }
+
+    fn path(&self, date: &str, index: usize) -> PathBuf {
+        self.directory.join(self.name(date, index))
+    }
+
+    fn is_compressed(&self, _date: &str, _index: usize) -> bool {
+        #[cfg(feature = "gzip")]
+        return self
+            .directory
+            .join(self.name(_date, _index) + GZ)
+            .exists();
+
+        #[cfg(not(feature = "gzip"))]
+        return false;
+    }
+
+    fn exists(&self, date: &str, index: usize) -> bool {
+        self.path(date, index).exists() || self.is_compressed(date, index)
+    }
+
+    /// Returns whether a file name could have been generated by the pattern.
+    fn matches(&self, name: &str) -> bool {
+        #[cfg(feature = "gzip")]
+        let name = name.strip_suffix(GZ).unwrap_or(name);
+
+        let pattern = self.pattern.replace(INDEX, DATE);
+        let mut literals = pattern.split(DATE);
+        let first = literals.next().unwrap_or_default();
+
+        let mut rest = match name.strip_prefix(first) {
+            Some(rest) => rest,
+            None => return false,
+        };
+
+        let mut literals: Vec<&str> = literals.collect();
+        let last = match literals.pop() {
+            Some(last) => last,
+            // The pattern has no placeholders
+            None => return rest.is_empty(),
+        };
+
+        for literal in literals {
+            match rest.find(literal) {
+                Some(i) => rest = &rest[i + literal.len()..],
+                None => return false,
+            }
+        }
+
+        rest.ends_with(last)
+    }
+}
+
+impl State {
+    fn is_full(&self) -> bool {
+        matches!(self.config.max_bytes, Some(max) if self.size >= max)
+    }
+
+    fn refresh(&mut self) -> io::Result<()> {
+        if self.config.rotation != Rotation::Never {
+            let date = self.config.date();
+
+            if date != self.date {
+                return self.rotate(date, 0);
+            }
+        }
+
+        if self.is_full() {
+            self.rotate(self.date.clone(), self.index + 1)?;
+        }
+
+        Ok(())
+    }
+
+    fn rotate(&mut self, date: String, index: usize) -> io::Result<()> {
+        let path = self.config.path(&date, index);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+
+        // Dropping the old file closes it before it's compressed.
+        drop(mem::replace(&mut self.file, file));
+        let old_path = mem::replace(&mut self.path, path);
+        self.date = date;
+        self.index = index;
+        self.size = size;
+
+        // Compressing can take a while, so it's left to the writer once it no
+        // longer holds the lock.
+        #[cfg(feature = "gzip")]
+        if self.config.compress && old_path != self.path {
+            self.uncompressed.push(old_path);
+        }
+        #[cfg(not(feature = "gzip"))]
+        let _ = old_path;
+
+        self.remove_expired()
+    }
+
+    fn remove_expired(&self) -> io::Result<()> {
+        let max_files = match self.config.max_files {
+            Some(max_files) => max_files,
+            None => return Ok(()),
+        };
+
+        let mut rotated: Vec<(SystemTime, PathBuf)> = Vec::new();
+        for entry in fs::read_dir(&self.config.directory)? {
+            let entry = entry?;
+            let path = entry.path();
+            let matches = entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| self.config.matches(name));
+
+            if matches && path != self.path && entry.file_type()?.is_file() {
+                let modified = entry.metadata()?.modified()?;
+                rotated.push((modified, path));
+            }
+        }
+
+        if rotated.len() > max_files {
+            rotated.sort();
+            for (_, path) in &rotated[..rotated.len() - max_files] {
+                fs::remove_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "gzip")]
+fn compress(path: &Path) -> io::Result<()> {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    let mut gz_path = path.as_os_str().to_owned();
+    gz_path.push(GZ);
+
+    let mut input = match File::open(path) {
+        Ok(input) => input,
+        // The file was already deleted for exceeding the retention count.
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(error) => return Err(error),
+    };
+    let mut encoder = GzEncoder::new(File::create(gz_path)?, Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?;
+
+    fs::remove_file(path)
+}
+
+impl<'a> MakeWriter<'a> for RollingFile {
+    type Writer = RollingWriter<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        let mut state = self.lock();
+        let error = state.refresh().err();
+        #[cfg(feature = "gzip")]
+        let (state, error) = self.compress_rotated(state, error);
+        RollingWriter { state, error }
+    }
+}
+
+impl io::Write for RollingWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+
+        let written = self.state.file.write(buf)?;
+        self.state.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.state.file.flush()
+    }
+}
@@ -0,0 +1,371 @@
+//! Accumulate trees and process them in batches.
+//!
+//! See [`Batch`] for more details.
+use crate::printer::{MakeStderr, Pretty, Printer};
+use crate::processor::{self, Processor};
+use crate::tree::{Field, Tree};
+use std::error;
+use std::mem;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Error type returned if a [`BatchProcessor`] fails.
+#[derive(Error, Debug)]
+#[error("{source}")]
+pub struct Error {
+    /// The recoverable [`Tree`]s that couldn't be processed.
+    pub trees: Vec<Tree>,
+
+    source: Box<dyn error::Error + Send + Sync>,
+}
+
+/// Create an error for when a [`BatchProcessor`] fails to process a batch of [`Tree`]s.
+pub fn error(trees: Vec<Tree>, source: Box<dyn error::Error + Send + Sync>) -> Error {
+    Error { trees, source }
+}
+
+/// The result type of [`BatchProcessor::process_batch`].
+pub type Result = std::result::Result<(), Error>;
+
+/// A trait for processing many completed [`Tree`]s at once.
+///
+/// This is the batch analog of [`Processor`], and is intended for sinks where
+/// each write has a fixed cost, like files and sockets. It is used by [`Batch`].
+///
+/// [`Printer`] implements this trait by formatting each tree and writing the
+/// results with a single call to [`write_all`](std::io::Write::write_all).
+pub trait BatchProcessor: 'static {
+    /// Process a batch of [`Tree`]s.
+    ///
+    /// # Errors
+    ///
+    /// If the batch cannot be processed, then the trees are returned along with
+    /// a `Box<dyn Error + Send + Sync>`, and are deferred to the fallback
+    /// processor of the [`Batch`].
+    fn process_batch(&self, trees: Vec<Tree>) -> Result;
+}
+
+impl<P: BatchProcessor> BatchProcessor for Box<P> {
+    fn process_batch(&self, trees: Vec<Tree>) -> Result {
+        self.as_ref().process_batch(trees)
+    }
+}
+
+impl<P: BatchProcessor> BatchProcessor for Arc<P> {
+    fn process_batch(&self, trees: Vec<Tree>) -> Result {
+        self.as_ref().process_batch(trees)
+    }
+}
+
+/// A [`Processor`] that accumulates trees and hands them to a [`BatchProcessor`].
+///
+/// A batch is flushed when any of the following is reached:
+/// * The number of buffered trees reaches [`max_trees`].
+/// * The approximate in-memory size of the buffered trees reaches [`max_bytes`].
+/// * The oldest buffered tree has waited for the [`interval`], if one is set.
+///
+/// Any remaining trees are flushed when the `Batch` is dropped, which happens
+/// when the worker task of a [`Runtime`] shuts down.
+///
+/// If the `BatchProcessor` fails, each tree in the batch is deferred to the
+/// fallback processor, which pretty-prints to stderr by default and can be set
+/// with [`on_error`]. If the fallback fails too, the first error is returned
+/// and the others are printed to stderr.
+///
+/// [`max_trees`]: Builder::max_trees
+/// [`max_bytes`]: Builder::max_bytes
+/// [`interval`]: Builder::interval
+/// [`on_error`]: Builder::on_error
+/// [`Runtime`]: crate::runtime::Runtime
+///
+/// # Examples
+///
+/// Writing to a file in batches from the worker task.
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use std::fs::File;
+/// use std::time::Duration;
+/// use tracing_forest::processor::batch::Batch;
+///
+/// let out = File::create("out.log").unwrap();
+///
+/// tracing_forest::worker_task()
+///     .map_receiver(|printer| Batch::builder(printer.writer(out))
+///         .max_trees(100)
+///         .interval(Duration::from_secs(1))
+///         .build()
+///     )
+///     .build()
+///     .on(async {
+///         // ...
+///     })
+///     .await;
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Batch<S: BatchProcessor, F: Processor = Printer<Pretty, MakeStderr>> {
+    shared: Arc<Shared<S, F>>,
+    flusher: Option<JoinHandle<()>>,
+    max_trees: usize,
+    max_bytes: usize,
+}
+
+/// Incrementally construct a [`Batch`].
+///
+/// See [`Batch::builder`] for more details.
+#[derive(Debug)]
+pub struct Builder<S, F> {
+    sink: S,
+    fallback: F,
+    max_trees: usize,
+    max_bytes: usize,
+    interval: Option<Duration>,
+}
+
+#[derive(Debug)]
+struct Shared<S, F> {
+    sink: S,
+    fallback: F,
+    buffer: Mutex<Buffer>,
+    signal: Condvar,
+    // Held from taking the buffered trees until they're written, so that
+    // batches are never written concurrently or out of order.
+    flushing: Mutex<()>,
+}
+
+#[derive(Debug, Default)]
+struct Buffer {
+    trees: Vec<Tree>,
+    bytes: usize,
+    since: Option<Instant>,
+    shutdown: bool,
+}
+
+impl<S: BatchProcessor> Batch<S> {
+    /// Begin configuring a `Batch` that hands trees to `sink`.
+    ///
+    /// By default, batches are flushed every 64 trees or 1 MiB, with no interval.
+    pub fn builder(sink: S) -> Builder<S, Printer<Pretty, MakeStderr>> {
+        Builder {
+            sink,
+            fallback: Printer::new().writer(MakeStderr),
+            max_trees: 64,
+            max_bytes: 1 << 20,
+            interval: None,
+        }
+    }
+}
+
+impl<S, F> Builder<S, F>
+where
+    S: BatchProcessor + Send + Sync,
+    F: Processor + Send + Sync,
+{
+    /// Set the number of trees that triggers a flush.
+    pub fn max_trees(mut self, max_trees: usize) -> Self {
+        self.max_trees = max_trees;
+        self
+    }
+
+    /// Set the approximate number of bytes of buffered trees that triggers a flush.
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Set the longest duration a tree can stay buffered before a flush.
+    ///
+    /// Setting an interval spawns a thread that flushes the buffer even when
+    /// no new trees arrive.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    /// Set the processor that trees are deferred to if a batch fails.
+    pub fn on_error<F2>(self, fallback: F2) -> Builder<S, F2>
+    where
+        F2: Processor + Send + Sync,
+    {
+        Builder {
+            sink: self.sink,
+            fallback,
+            max_trees: self.max_trees,
+            max_bytes: self.max_bytes,
+            interval: self.interval,
+        }
+    }
+
+    /// Complete the [`Batch`].
+    pub fn build(self) -> Batch<S, F> {
+        let shared = Arc::new(Shared {
+            sink: self.sink,
+            fallback: self.fallback,
+            buffer: Mutex::new(Buffer::default()),
+            signal: Condvar::new(),
+            flushing: Mutex::new(()),
+        });
+
+        let flusher = self.interval.map(|interval| {
+            let shared = shared.clone();
+            thread::Builder::new()
+                .name("tracing-forest-batch".into())
+                .spawn(move || shared.run_flusher(interval))
+                .expect("failed to spawn the batch flushing thread")
+        });
+
+        Batch {
+            shared,
+            flusher,
+            max_trees: self.max_trees,
+            max_bytes: self.max_bytes,
+        }
+    }
+}
+
+impl<S, F> Shared<S, F>
+where
+    S: BatchProcessor,
+    F: Processor,
+{
+    #[allow(clippy::result_large_err)]
+    fn flush(&self) -> processor::Result {
+        let _flushing = self.flushing.lock().expect(POISONED);
+        let trees = self.buffer.lock().expect(POISONED).take();
+        self.write(trees)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn write(&self, trees: Vec<Tree>) -> processor::Result {
+        if trees.is_empty() {
+            return Ok(());
+        }
+
+        let err = match self.sink.process_batch(trees) {
+            Ok(()) => return Ok(()),
+            Err(err) => err,
+        };
+
+        eprintln!("{}, using fallback processor...", err);
+
+        let mut result = Ok(());
+        for tree in err.trees {
+            if let Err(err) = self.fallback.process(tree) {
+                // Only the first error is returned, so the rest are reported
+                // here instead of being dropped silently.
+                match result {
+                    Ok(()) => result = Err(err),
+                    Err(_) => eprintln!("{}, dropping the tree", err),
+                }
+            }
+        }
+        result
+    }
+
+    fn run_flusher(&self, interval: Duration) {
+        let mut buffer = self.buffer.lock().expect(POISONED);
+        loop {
+            if buffer.shutdown {
+                return;
+            }
+
+            buffer = match buffer.since {
+                Some(since) if since.elapsed() >= interval => {
+                    drop(buffer);
+                    if let Err(err) = self.flush() {
+                        eprintln!("{}, dropping the tree", err);
+                    }
+                    self.buffer.lock().expect(POISONED)
+                }
+                Some(since) => {
+                    let timeout = interval.saturating_sub(since.elapsed());
+                    self.signal.wait_timeout(buffer, timeout).expect(POISONED).0
+                }
+                None => self.signal.wait(buffer).expect(POISONED),
+            };
+        }
+    }
+}
+
+impl Buffer {
+    fn take(&mut self) -> Vec<Tree> {
+        self.bytes = 0;
+        self.since = None;
+        mem::take(&mut self.trees)
+    }
+}
+
+impl<S, F> Processor for Batch<S, F>
+where
+    S: BatchProcessor,
+    F: Processor,
+{
+    fn process(&self, tree: Tree) -> processor::Result {
+        {
+            let mut buffer = self.shared.buffer.lock().expect(POISONED);
+            if buffer.since.is_none() {
+                buffer.since = Some(Instant::now());
+                self.shared.signal.notify_one();
+            }
+            buffer.bytes += approximate_size(&tree);
+            buffer.trees.push(tree);
+
+            if buffer.trees.len() < self.max_trees && buffer.bytes < self.max_bytes {
+                return Ok(());
+            }
+        }
+
+        self.shared.flush()
+    }
+}
+
+impl<S, F> Drop for Batch<S, F>
+where
+    S: BatchProcessor,
+    F: Processor,
+{
+    fn drop(&mut self) {
+        if let Ok(mut buffer) = self.shared.buffer.lock() {
+            buffer.shutdown = true;
+        }
+        self.shared.signal.notify_one();
+
+        if let Some(flusher) = self.flusher.take() {
+            let _ = flusher.join();
+        }
+
+        let trees = match self.shared.buffer.lock() {
+            Ok(mut buffer) => buffer.take(),
+            Err(_) => return,
+        };
+
+        if let Err(err) = self.shared.write(trees) {
+            eprintln!("{}, dropping the tree", err);
+        }
+    }
+}
+
+const POISONED: &str = "Batch buffer poisoned, this is a bug";
+
+/// Approximates the number of bytes a [`Tree`] takes up in memory.
+fn approximate_size(tree: &Tree) -> usize {
+    let fields = |fields: &[Field]| -> usize {
+        fields
+            .iter()
+            .map(|field| field.key().len() + field.value().len())
+            .sum()
+    };
+
+    mem::size_of::<Tree>()
+        + match tree {
+            Tree::Event(event) => event.message().map_or(0, str::len) + fields(event.fields()),
+            Tree::Span(span) => {
+                span.name().len()
+                    + fields(&span.shared.fields)
+                    + span.nodes().iter().map(approximate_size).sum::<usize>()
+            }
+        }
+}
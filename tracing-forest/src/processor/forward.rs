@@ -0,0 +1,291 @@
+//! Forward trees as newline-delimited JSON over a socket.
+//!
+//! See [`Forward`] for more details.
+use crate::processor::{self, Processor};
+use crate::tree::Tree;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Error returned by [`Forward`] when a tree can neither be sent nor buffered.
+#[derive(Error, Debug)]
+#[error("Forwarding buffer is full ({0} trees) while disconnected")]
+pub struct BufferFullError(usize);
+
+/// The first line sent on each connection by a [`Forward`] configured with a
+/// [service name](Forward::service), identifying the producer to the collector.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Handshake {
+    /// The name of the service sending trees over the connection.
+    pub service: String,
+}
+
+/// A [`Processor`] that sends each tree as a line of JSON over a TCP or Unix
+/// domain socket.
+///
+/// Trees are serialized using the `serde` implementations in the [`tree`]
+/// module, and are terminated with a newline, making the stream newline-delimited
+/// JSON (NDJSON).
+///
+/// The connection is opened on the first tree. If it can't be opened or a write
+/// fails, trees are buffered in memory and the connection is retried on later
+/// trees, at most once per [reconnect interval][reconnect_interval]. Once the
+/// [buffer][buffer_capacity] is full, trees are rejected with a [`BufferFullError`],
+/// which defers them to the fallback processor added with [`Processor::or`].
+///
+/// If a [service name][service] is set, each connection starts with a
+/// [`Handshake`] line so that a collector receiving trees from many producers
+/// can tell them apart.
+///
+/// [`tree`]: crate::tree
+/// [reconnect_interval]: Forward::reconnect_interval
+/// [buffer_capacity]: Forward::buffer_capacity
+/// [service]: Forward::service
+///
+/// # Examples
+///
+/// Forwarding trees to a local collector, or else pretty-printing to stderr.
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use tracing_forest::processor::forward::Forward;
+/// use tracing_forest::traits::*;
+///
+/// tracing_forest::worker_task()
+///     .map_receiver(|_| Forward::tcp("127.0.0.1:7878").or_stderr())
+///     .build()
+///     .on(async {
+///         // ...
+///     })
+///     .await;
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Forward {
+    address: Address,
+    buffer_capacity: usize,
+    reconnect_interval: Duration,
+    write_timeout: Option<Duration>,
+    handshake: Option<String>,
+    state: Mutex<State>,
+}
+
+#[derive(Clone, Debug)]
+enum Address {
+    Tcp(String),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+#[derive(Debug)]
+enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+#[derive(Debug, Default)]
+struct State {
+    stream: Option<Stream>,
+    last_attempt: Option<Instant>,
+    buffer: VecDeque<String>,
+}
+
+impl Forward {
+    /// Create a `Forward` that connects to a TCP address, like `"127.0.0.1:7878"`.
+    pub fn tcp(address: impl Into<String>) -> Self {
+        Forward::new(Address::Tcp(address.into()))
+    }
+
+    /// Create a `Forward` that connects to a Unix domain socket.
+    #[cfg(unix)]
+    #[cfg_attr(docsrs, doc(cfg(unix)))]
+    pub fn unix(path: impl Into<PathBuf>) -> Self {
+        Forward::new(Address::Unix(path.into()))
+    }
+
+    fn new(address: Address) -> Self {
+        Forward {
+            address,
+            buffer_capacity: 1024,
+            reconnect_interval: Duration::from_secs(1),
+            write_timeout: Some(Duration::from_secs(5)),
+            handshake: None,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Set the number of trees buffered while disconnected. Defaults to 1024.
+    pub fn buffer_capacity(mut self, buffer_capacity: usize) -> Self {
+        self.buffer_capacity = buffer_capacity;
+        self
+    }
+
+    /// Set the minimum time between connection attempts. Defaults to 1 second.
+    pub fn reconnect_interval(mut self, reconnect_interval: Duration) -> Self {
+        self.reconnect_interval = reconnect_interval;
+        self
+    }
+
+    /// Set the timeout for connecting and writing, or `None` to block
+    /// indefinitely. Defaults to 5 seconds.
+    pub fn write_timeout(mut self, write_timeout: Option<Duration>) -> Self {
+        self.write_timeout = write_timeout;
+        self
+    }
+
+    /// Set the service name sent in a [`Handshake`] at the start of each connection.
+    pub fn service(mut self, service: impl Into<String>) -> Self {
+        let handshake = Handshake {
+            service: service.into(),
+        };
+        let mut line = serde_json::to_string(&handshake).expect("handshake is valid JSON");
+        line.push('\n');
+        self.handshake = Some(line);
+        self
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn connect(&self) -> io::Result<Stream> {
+        let mut stream = self.open()?;
+        if let Some(handshake) = &self.handshake {
+            stream.write_all(handshake.as_bytes())?;
+        }
+        Ok(stream)
+    }
+
+    fn open(&self) -> io::Result<Stream> {
+        match &self.address {
+            Address::Tcp(address) => {
+                let mut last_err = None;
+                for addr in address.to_socket_addrs()? {
+                    let stream = match self.write_timeout {
+                        Some(timeout) => TcpStream::connect_timeout(&addr, timeout),
+                        None => TcpStream::connect(addr),
+                    };
+                    match stream {
+                        Ok(stream) => {
+                            stream.set_write_timeout(self.write_timeout)?;
+                            return Ok(Stream::Tcp(stream));
+                        }
+                        Err(err) => last_err = Some(err),
+                    }
+                }
+                Err(last_err.unwrap_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "address resolved to nothing")
+                }))
+            }
+            #[cfg(unix)]
+            Address::Unix(path) => {
+                let stream = UnixStream::connect(path)?;
+                stream.set_write_timeout(self.write_timeout)?;
+                Ok(Stream::Unix(stream))
+            }
+        }
+    }
+}
+
+impl State {
+    /// Writes buffered lines until the buffer is empty or a write fails.
+    fn flush(&mut self) {
+        while let (Some(stream), Some(line)) = (self.stream.as_mut(), self.buffer.front()) {
+            match stream.write_all(line.as_bytes()) {
+                Ok(()) => {
+                    self.buffer.pop_front();
+                }
+                Err(_) => self.disconnect(),
+            }
+        }
+    }
+
+    /// Closes the connection after a failed write.
+    ///
+    /// The failed line may have been partially written, so nothing else can be
+    /// written to the connection without corrupting the stream. The line is
+    /// sent again from the start once a new connection is opened.
+    fn disconnect(&mut self) {
+        if let Some(stream) = self.stream.take() {
+            let _ = stream.shutdown();
+        }
+    }
+}
+
+impl Processor for Forward {
+    fn process(&self, tree: Tree) -> processor::Result {
+        let mut line = match serde_json::to_string(&tree) {
+            Ok(line) => line,
+            Err(err) => return Err(processor::error(tree, err.into())),
+        };
+        line.push('\n');
+
+        let mut state = self.lock();
+
+        if state.stream.is_none() {
+            let now = Instant::now();
+            let retry = state
+                .last_attempt
+                .map_or(true, |last| now.duration_since(last) >= self.reconnect_interval);
+
+            if retry {
+                state.last_attempt = Some(now);
+                state.stream = self.connect().ok();
+            }
+        }
+
+        state.flush();
+
+        if let Some(stream) = state.stream.as_mut() {
+            match stream.write_all(line.as_bytes()) {
+                Ok(()) => return Ok(()),
+                Err(_) => state.disconnect(),
+            }
+        }
+
+        if state.buffer.len() >= self.buffer_capacity {
+            let err = BufferFullError(self.buffer_capacity);
+            return Err(processor::error(tree, err.into()));
+        }
+
+        state.buffer.push_back(line);
+        Ok(())
+    }
+}
+
+impl Stream {
+    fn shutdown(&self) -> io::Result<()> {
+        match self {
+            Stream::Tcp(stream) => stream.shutdown(Shutdown::Both),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.shutdown(Shutdown::Both),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.write(buf),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Stream::Tcp(stream) => stream.flush(),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.flush(),
+        }
+    }
+}
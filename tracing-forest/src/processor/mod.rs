@@ -0,0 +1,381 @@
+//! Trait for processing log trees on completion.
+//!
+//! See [`Processor`] for more details.
+use crate::printer::{MakeStderr, MakeStdout, Pretty, Printer};
+use crate::tree::Tree;
+use crate::{cfg_forward, cfg_sqlite, cfg_tokio};
+use std::error;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use thiserror::Error;
+
+pub mod batch;
+
+cfg_forward! {
+    pub mod forward;
+}
+
+cfg_sqlite! {
+    pub mod sqlite;
+}
+
+/// Error type returned if a [`Processor`] fails.
+#[derive(Error, Debug)]
+#[error("{source}")]
+pub struct Error {
+    /// The recoverable [`Tree`] type that couldn't be processed.
+    pub tree: Tree,
+
+    source: Box<dyn error::Error + Send + Sync>,
+}
+
+/// Create an error for when a [`Processor`] fails to process a [`Tree`].
+pub fn error(tree: Tree, source: Box<dyn error::Error + Send + Sync>) -> Error {
+    Error { tree, source }
+}
+
+/// The result type of [`Processor::process`].
+pub type Result = std::result::Result<(), Error>;
+
+/// The future type returned by [`AsyncProcessor::process`].
+pub type BoxFuture<'a> = Pin<Box<dyn Future<Output = Result> + Send + 'a>>;
+
+/// A trait for processing completed [`Tree`]s.
+///
+/// `Processor`s are responsible for both formatting and writing logs to their
+/// intended destinations. This is typically implemented using
+/// [`Formatter`], [`MakeWriter`], and [`io::Write`].
+///
+/// While this trait may be implemented on downstream types, [`from_fn`]
+/// provides a convenient interface for creating `Processor`s without having to
+/// explicitly define new types.
+///
+/// [trace trees]: crate::tree::Tree
+/// [`Formatter`]: crate::printer::Formatter
+/// [`MakeWriter`]: tracing_subscriber::fmt::MakeWriter
+/// [`io::Write`]: std::io::Write
+pub trait Processor: 'static + Sized {
+    /// Process a [`Tree`]. This can mean many things, such as writing to
+    /// stdout or a file, sending over a network, storing in memory, ignoring,
+    /// or anything else.
+    ///
+    /// # Errors
+    ///
+    /// If the `Tree` cannot be processed, then it is returned along with a
+    /// `Box<dyn Error + Send + Sync>`. If the processor is configured with a
+    /// fallback processor from [`Processor::or`], then the `Tree` is deferred
+    /// to that processor.
+    // The error carries the unprocessed `Tree` so that it can be recovered.
+    #[allow(clippy::result_large_err)]
+    fn process(&self, tree: Tree) -> Result;
+
+    /// Returns a `Processor` that first attempts processing with `self`, and
+    /// resorts to processing with `fallback` on failure.
+    ///
+    /// Note that [`or_stdout`], [`or_stderr`], and [`or_none`] can be used as
+    /// shortcuts for pretty printing or dropping the `Tree` entirely.
+    ///
+    /// [`or_stdout`]: Processor::or_stdout
+    /// [`or_stderr`]: Processor::or_stderr
+    /// [`or_none`]: Processor::or_none
+    fn or<P: Processor>(self, processor: P) -> WithFallback<Self, P> {
+        WithFallback {
+            primary: self,
+            fallback: processor,
+        }
+    }
+
+    /// Returns a `Processor` that first attempts processing with `self`, and
+    /// resorts to pretty-printing to stdout on failure.
+    fn or_stdout(self) -> WithFallback<Self, Printer<Pretty, MakeStdout>> {
+        self.or(Printer::new().writer(MakeStdout))
+    }
+
+    /// Returns a `Processor` that first attempts processing with `self`, and
+    /// resorts to pretty-printing to stderr on failure.
+    fn or_stderr(self) -> WithFallback<Self, Printer<Pretty, MakeStderr>> {
+        self.or(Printer::new().writer(MakeStderr))
+    }
+
+    /// Returns a `Processor` that first attempts processing with `self`, otherwise
+    /// silently fails.
+    fn or_none(self) -> WithFallback<Self, Sink> {
+        self.or(Sink)
+    }
+}
+
+/// A [`Processor`] or [`AsyncProcessor`] composed of a primary and a fallback
+/// processor.
+///
+/// This type is returned by [`Processor::or`] and [`AsyncProcessor::or`].
+#[derive(Debug)]
+pub struct WithFallback<P, F> {
+    primary: P,
+    fallback: F,
+}
+
+/// A [`Processor`] that ignores any incoming logs.
+///
+/// This processor cannot fail.
+#[derive(Debug)]
+pub struct Sink;
+
+/// A [`Processor`] that processes incoming logs via a function.
+///
+/// Instances of `FromFn` are returned by the [`from_fn`] function.
+#[derive(Debug)]
+pub struct FromFn<F>(F);
+
+/// Create a processor that processes incoming logs via a function.
+///
+/// # Examples
+///
+/// Internally, [`worker_task`] uses `from_fn` to allow the subscriber to send
+/// trace data across a channel to a processing task.
+/// ```
+/// use tokio::sync::mpsc;
+/// use tracing_forest::processor;
+///
+/// let (tx, rx) = mpsc::unbounded_channel();
+///
+/// let sender_processor = processor::from_fn(move |tree| tx
+///     .send(tree)
+///     .map_err(|err| {
+///         let msg = err.to_string().into();
+///         processor::error(err.0, msg)
+///     })
+/// );
+///
+/// // -- snip --
+/// ```
+///
+/// [`worker_task`]: crate::runtime::worker_task
+pub fn from_fn<F>(f: F) -> FromFn<F>
+where
+    F: 'static + Fn(Tree) -> Result,
+{
+    FromFn(f)
+}
+
+impl<P, F> Processor for WithFallback<P, F>
+where
+    P: Processor,
+    F: Processor,
+{
+    #[allow(clippy::result_large_err)]
+    fn process(&self, tree: Tree) -> Result {
+        self.primary.process(tree).or_else(|err| {
+            eprintln!("{}, using fallback processor...", err);
+            self.fallback.process(err.tree)
+        })
+    }
+}
+
+impl Processor for Sink {
+    fn process(&self, _tree: Tree) -> Result {
+        Ok(())
+    }
+}
+
+impl<F> Processor for FromFn<F>
+where
+    F: 'static + Fn(Tree) -> Result,
+{
+    fn process(&self, tree: Tree) -> Result {
+        (self.0)(tree)
+    }
+}
+
+impl<P: Processor> Processor for Box<P> {
+    fn process(&self, tree: Tree) -> Result {
+        self.as_ref().process(tree)
+    }
+}
+
+impl<P: Processor> Processor for Arc<P> {
+    fn process(&self, tree: Tree) -> Result {
+        self.as_ref().process(tree)
+    }
+}
+
+/// A trait for processing completed [`Tree`]s asynchronously.
+///
+/// This is the asynchronous analog of [`Processor`], and is intended for
+/// processors that write to async sinks, like Tokio files or sockets. It is
+/// used by the worker task when configured with [`Builder::map_receiver_async`],
+/// which `.await`s each tree before receiving the next.
+///
+/// Synchronous processors can be adapted with [`blocking`], and closures
+/// returning futures can be used via [`from_async_fn`].
+///
+/// [`Builder::map_receiver_async`]: crate::runtime::Builder::map_receiver_async
+pub trait AsyncProcessor: 'static + Send + Sync {
+    /// Process a [`Tree`] asynchronously.
+    ///
+    /// # Errors
+    ///
+    /// If the `Tree` cannot be processed, then it is returned along with a
+    /// `Box<dyn Error + Send + Sync>`, as in [`Processor::process`]. If the
+    /// processor is configured with a fallback processor from
+    /// [`AsyncProcessor::or`], then the `Tree` is deferred to that processor.
+    fn process(&self, tree: Tree) -> BoxFuture<'_>;
+
+    /// Returns an `AsyncProcessor` that first attempts processing with `self`,
+    /// and resorts to processing with `fallback` on failure.
+    ///
+    /// Synchronous fallbacks, like printing to stderr, can be adapted with
+    /// [`blocking`].
+    fn or<P: AsyncProcessor>(self, processor: P) -> WithFallback<Self, P>
+    where
+        Self: Sized,
+    {
+        WithFallback {
+            primary: self,
+            fallback: processor,
+        }
+    }
+}
+
+impl<P, F> AsyncProcessor for WithFallback<P, F>
+where
+    P: AsyncProcessor,
+    F: AsyncProcessor,
+{
+    fn process(&self, tree: Tree) -> BoxFuture<'_> {
+        Box::pin(async move {
+            match self.primary.process(tree).await {
+                Ok(()) => Ok(()),
+                Err(err) => {
+                    eprintln!("{}, using fallback processor...", err);
+                    self.fallback.process(err.tree).await
+                }
+            }
+        })
+    }
+}
+
+/// An [`AsyncProcessor`] that processes incoming logs via a function returning
+/// a future.
+///
+/// Instances of `FromAsyncFn` are returned by the [`from_async_fn`] function.
+#[derive(Debug)]
+pub struct FromAsyncFn<F>(F);
+
+/// Create an async processor that processes incoming logs via a function
+/// returning a future.
+///
+/// # Examples
+///
+/// Writing pretty-printed logs to a Tokio file.
+/// ```no_run
+/// use std::sync::Arc;
+/// use tokio::io::AsyncWriteExt;
+/// use tokio::sync::Mutex;
+/// use tracing_forest::{processor, Formatter, printer::Pretty};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let file = Arc::new(Mutex::new(tokio::fs::File::create("out.log").await.unwrap()));
+///
+/// let file_processor = processor::from_async_fn(move |tree| {
+///     let file = file.clone();
+///     async move {
+///         let string = match Pretty.fmt(&tree) {
+///             Ok(string) => string,
+///             Err(err) => return Err(processor::error(tree, err.into())),
+///         };
+///         match file.lock().await.write_all(string.as_bytes()).await {
+///             Ok(()) => Ok(()),
+///             Err(err) => Err(processor::error(tree, err.into())),
+///         }
+///     }
+/// });
+///
+/// // -- snip --
+/// # }
+/// ```
+pub fn from_async_fn<F, Fut>(f: F) -> FromAsyncFn<F>
+where
+    F: 'static + Send + Sync + Fn(Tree) -> Fut,
+    Fut: 'static + Send + Future<Output = Result>,
+{
+    FromAsyncFn(f)
+}
+
+impl<F, Fut> AsyncProcessor for FromAsyncFn<F>
+where
+    F: 'static + Send + Sync + Fn(Tree) -> Fut,
+    Fut: 'static + Send + Future<Output = Result>,
+{
+    fn process(&self, tree: Tree) -> BoxFuture<'_> {
+        Box::pin((self.0)(tree))
+    }
+}
+
+impl<P: AsyncProcessor> AsyncProcessor for Box<P> {
+    fn process(&self, tree: Tree) -> BoxFuture<'_> {
+        self.as_ref().process(tree)
+    }
+}
+
+impl<P: AsyncProcessor> AsyncProcessor for Arc<P> {
+    fn process(&self, tree: Tree) -> BoxFuture<'_> {
+        self.as_ref().process(tree)
+    }
+}
+
+cfg_tokio! {
+    /// An [`AsyncProcessor`] that runs a synchronous [`Processor`] on Tokio's
+    /// blocking thread pool.
+    ///
+    /// Instances of `Blocking` are returned by the [`blocking`] function.
+    #[derive(Debug)]
+    pub struct Blocking<P>(Arc<P>);
+
+    /// Create an async processor that runs a synchronous processor using
+    /// [`tokio::task::spawn_blocking`], so that blocking writes don't stall
+    /// the worker task's thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tracing_forest::{processor, traits::*};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     tracing_forest::worker_task()
+    ///         .map_receiver_async(|printer| processor::blocking(printer.or_stderr()))
+    ///         .build()
+    ///         .on(async {
+    ///             // ...
+    ///         })
+    ///         .await;
+    /// }
+    /// ```
+    pub fn blocking<P>(processor: P) -> Blocking<P>
+    where
+        P: Processor + Send + Sync,
+    {
+        Blocking(Arc::new(processor))
+    }
+
+    impl<P> AsyncProcessor for Blocking<P>
+    where
+        P: Processor + Send + Sync,
+    {
+        #[allow(clippy::result_large_err)]
+        fn process(&self, tree: Tree) -> BoxFuture<'_> {
+            let processor = self.0.clone();
+            Box::pin(async move {
+                match tokio::task::spawn_blocking(move || processor.process(tree)).await {
+                    Ok(result) => result,
+                    Err(err) => match err.try_into_panic() {
+                        Ok(payload) => std::panic::resume_unwind(payload),
+                        Err(err) => panic!("{}", err),
+                    },
+                }
+            })
+        }
+    }
+}
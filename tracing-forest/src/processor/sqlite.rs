@@ -0,0 +1,304 @@
+//! Store trees in a SQLite database for ad-hoc queries.
+//!
+//! See [`Sqlite`] for more details.
+//!
+//! # Schema
+//!
+//! Every tree gets a row in `trees`, and each of its spans, events, and fields
+//! gets a row in the table of the same name. The tables are created if they
+//! don't exist, and the schema is never altered, so a database can be written
+//! to by any version of this processor without migrations.
+//!
+//! ```sql
+#![doc = include_str!("sqlite/schema.sql")]
+//! ```
+//!
+//! * `path` is the names of a span and its ancestors, joined with `/`, like
+//!   `checkout/payment/db.query`. `depth` is 0 for the root span.
+//! * Durations are in nanoseconds, where `base_nanos` is `total_nanos` minus
+//!   the time spent in child spans.
+//! * `uuid` and `timestamp` are `NULL` unless the `uuid` and `chrono` features
+//!   are enabled. Timestamps are RFC 3339 strings, which sort chronologically.
+//! * `span_id` is `NULL` for events that aren't inside a span, and each field
+//!   belongs to either a span or an event.
+//!
+//! # Example queries
+//!
+//! The slowest `db.query` spans under `checkout`:
+//! ```sql
+//! SELECT path, total_nanos / 1e6 AS millis
+//! FROM spans
+//! WHERE name = 'db.query' AND path LIKE 'checkout/%'
+//! ORDER BY total_nanos DESC
+//! LIMIT 10;
+//! ```
+//!
+//! The spans with the most time spent outside of their children:
+//! ```sql
+//! SELECT name, count(*) AS count, sum(base_nanos) / 1e6 AS millis
+//! FROM spans
+//! GROUP BY name
+//! ORDER BY millis DESC;
+//! ```
+//!
+//! Errors, along with the span and tree they occurred in:
+//! ```sql
+//! SELECT events.timestamp, spans.path, events.tag, events.message
+//! FROM events
+//! LEFT JOIN spans ON spans.id = events.span_id
+//! WHERE events.level = 'ERROR'
+//! ORDER BY events.timestamp;
+//! ```
+//!
+//! Requests for a given user, by span field:
+//! ```sql
+//! SELECT trees.timestamp, trees.name, trees.total_nanos / 1e6 AS millis
+//! FROM trees
+//! JOIN spans ON spans.tree_id = trees.id AND spans.depth = 0
+//! JOIN fields ON fields.span_id = spans.id
+//! WHERE fields.key = 'user' AND fields.value = '"alice"';
+//! ```
+//!
+//! Since field values are formatted with `Debug`, strings keep their quotes.
+use crate::processor::{self, Processor};
+use crate::tree::{Event, Field, Shared, Span, Tree};
+use rusqlite::{params, Connection, Transaction};
+use std::convert::TryFrom;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+pub use rusqlite;
+
+/// The statements creating the tables and indexes, if they don't exist.
+const SCHEMA: &str = include_str!("sqlite/schema.sql");
+
+/// A [`Processor`] that inserts each tree into a SQLite database.
+///
+/// Each tree is inserted in its own transaction, so readers never see a
+/// partially written tree. The database is opened in [WAL mode] when possible,
+/// allowing it to be queried while trees are being written.
+///
+/// See the [module-level documentation](self) for the schema and example
+/// queries.
+///
+/// [WAL mode]: https://www.sqlite.org/wal.html
+///
+/// # Examples
+///
+/// Storing trees in `traces.db`, or else pretty-printing to stderr.
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use tracing_forest::processor::sqlite::Sqlite;
+/// use tracing_forest::traits::*;
+///
+/// let sqlite = Sqlite::open("traces.db")?;
+///
+/// tracing_forest::worker_task()
+///     .map_receiver(|_| sqlite.or_stderr())
+///     .build()
+///     .on(async {
+///         // ...
+///     })
+///     .await;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Sqlite {
+    connection: Mutex<Connection>,
+}
+
+impl Sqlite {
+    /// Open or create a database at a path, creating the tables if they don't
+    /// exist.
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        let connection = Connection::open(path)?;
+        // In-memory and read-only databases can't use WAL, which is fine.
+        let _: rusqlite::Result<String> =
+            connection.query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0));
+        Sqlite::from_connection(connection)
+    }
+
+    /// Create a `Sqlite` from an open connection, creating the tables if they
+    /// don't exist.
+    pub fn from_connection(connection: Connection) -> rusqlite::Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Sqlite {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    /// Returns the connection, for querying the trees written so far.
+    pub fn connection(&self) -> MutexGuard<'_, Connection> {
+        // A panic while inserting rolls back the transaction, so the
+        // connection is still usable.
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn insert(&self, tree: &Tree) -> rusqlite::Result<()> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+
+        let (shared, name, total_nanos) = match tree {
+            Tree::Event(event) => (&event.shared, None, None),
+            Tree::Span(span) => (
+                &span.shared,
+                Some(span.name()),
+                Some(nanos(span.total_duration())),
+            ),
+        };
+        transaction.execute(
+            "INSERT INTO trees (uuid, timestamp, level, name, total_nanos) \
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                uuid(shared),
+                timestamp(shared),
+                shared.level.as_str(),
+                name,
+                total_nanos
+            ],
+        )?;
+
+        let mut insert = Insert {
+            transaction: &transaction,
+            tree_id: transaction.last_insert_rowid(),
+            path: String::new(),
+        };
+        insert.tree(tree, None, 0)?;
+
+        transaction.commit()
+    }
+}
+
+impl Processor for Sqlite {
+    fn process(&self, tree: Tree) -> processor::Result {
+        match self.insert(&tree) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(processor::error(tree, e.into())),
+        }
+    }
+}
+
+/// The state of inserting a single tree.
+struct Insert<'a> {
+    transaction: &'a Transaction<'a>,
+    tree_id: i64,
+    /// The path of the current span.
+    path: String,
+}
+
+impl Insert<'_> {
+    fn tree(&mut self, tree: &Tree, span_id: Option<i64>, depth: usize) -> rusqlite::Result<()> {
+        match tree {
+            Tree::Event(event) => self.event(event, span_id),
+            Tree::Span(span) => self.span(span, span_id, depth),
+        }
+    }
+
+    fn event(&mut self, event: &Event, span_id: Option<i64>) -> rusqlite::Result<()> {
+        self.transaction.execute(
+            "INSERT INTO events (tree_id, span_id, uuid, timestamp, level, tag, message) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                self.tree_id,
+                span_id,
+                uuid(&event.shared),
+                timestamp(&event.shared),
+                event.level().as_str(),
+                event.tag().map(|tag| tag.to_string()),
+                event.message(),
+            ],
+        )?;
+
+        let event_id = self.transaction.last_insert_rowid();
+        self.fields(event.fields(), None, Some(event_id))
+    }
+
+    fn span(
+        &mut self,
+        span: &Span,
+        parent_id: Option<i64>,
+        depth: usize,
+    ) -> rusqlite::Result<()> {
+        let parent_len = self.path.len();
+        if depth > 0 {
+            self.path.push('/');
+        }
+        self.path.push_str(span.name());
+
+        self.transaction.execute(
+            "INSERT INTO spans (tree_id, parent_id, uuid, timestamp, level, name, path, depth, \
+             total_nanos, inner_nanos, base_nanos) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                self.tree_id,
+                parent_id,
+                uuid(&span.shared),
+                timestamp(&span.shared),
+                span.level().as_str(),
+                span.name(),
+                self.path,
+                depth as i64,
+                nanos(span.total_duration()),
+                nanos(span.inner_duration()),
+                nanos(span.base_duration()),
+            ],
+        )?;
+
+        let span_id = self.transaction.last_insert_rowid();
+        self.fields(span.fields(), Some(span_id), None)?;
+
+        for node in span.nodes() {
+            self.tree(node, Some(span_id), depth + 1)?;
+        }
+
+        self.path.truncate(parent_len);
+        Ok(())
+    }
+
+    fn fields(
+        &self,
+        fields: &[Field],
+        span_id: Option<i64>,
+        event_id: Option<i64>,
+    ) -> rusqlite::Result<()> {
+        if fields.is_empty() {
+            return Ok(());
+        }
+
+        let mut statement = self.transaction.prepare_cached(
+            "INSERT INTO fields (span_id, event_id, key, value) VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        for field in fields {
+            statement.execute(params![span_id, event_id, field.key(), field.value()])?;
+        }
+        Ok(())
+    }
+}
+
+fn nanos(duration: std::time::Duration) -> i64 {
+    i64::try_from(duration.as_nanos()).unwrap_or(i64::MAX)
+}
+
+#[cfg(feature = "uuid")]
+fn uuid(shared: &Shared) -> Option<String> {
+    Some(shared.uuid.to_string())
+}
+
+#[cfg(not(feature = "uuid"))]
+fn uuid(_shared: &Shared) -> Option<String> {
+    None
+}
+
+#[cfg(feature = "chrono")]
+fn timestamp(shared: &Shared) -> Option<String> {
+    Some(shared.timestamp.to_rfc3339())
+}
+
+#[cfg(not(feature = "chrono"))]
+fn timestamp(_shared: &Shared) -> Option<String> {
+    None
+}
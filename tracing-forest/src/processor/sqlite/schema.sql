@@ -0,0 +1,52 @@
+CREATE TABLE IF NOT EXISTS trees (
+    id INTEGER PRIMARY KEY,
+    uuid TEXT,
+    timestamp TEXT,
+    level TEXT NOT NULL,
+    -- The name and duration of the root span, or NULL if the tree is an event.
+    name TEXT,
+    total_nanos INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS spans (
+    id INTEGER PRIMARY KEY,
+    tree_id INTEGER NOT NULL REFERENCES trees (id),
+    parent_id INTEGER REFERENCES spans (id),
+    uuid TEXT,
+    timestamp TEXT,
+    level TEXT NOT NULL,
+    name TEXT NOT NULL,
+    path TEXT NOT NULL,
+    depth INTEGER NOT NULL,
+    total_nanos INTEGER NOT NULL,
+    inner_nanos INTEGER NOT NULL,
+    base_nanos INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS events (
+    id INTEGER PRIMARY KEY,
+    tree_id INTEGER NOT NULL REFERENCES trees (id),
+    span_id INTEGER REFERENCES spans (id),
+    uuid TEXT,
+    timestamp TEXT,
+    level TEXT NOT NULL,
+    tag TEXT,
+    message TEXT
+);
+
+CREATE TABLE IF NOT EXISTS fields (
+    span_id INTEGER REFERENCES spans (id),
+    event_id INTEGER REFERENCES events (id),
+    key TEXT NOT NULL,
+    value TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS spans_tree_id ON spans (tree_id);
+CREATE INDEX IF NOT EXISTS spans_parent_id ON spans (parent_id);
+CREATE INDEX IF NOT EXISTS spans_name ON spans (name);
+CREATE INDEX IF NOT EXISTS events_tree_id ON events (tree_id);
+CREATE INDEX IF NOT EXISTS events_span_id ON events (span_id);
+CREATE INDEX IF NOT EXISTS events_level ON events (level);
+CREATE INDEX IF NOT EXISTS fields_span_id ON fields (span_id);
+CREATE INDEX IF NOT EXISTS fields_event_id ON fields (event_id);
+CREATE INDEX IF NOT EXISTS fields_key ON fields (key, value);
@@ -0,0 +1,292 @@
+//! Redact sensitive field values before they reach any processor.
+//!
+//! See [`Redaction`] for more details.
+use crate::cfg_regex;
+use std::borrow::Cow;
+use std::convert::TryInto;
+use std::fmt;
+
+cfg_regex! {
+    use regex::Regex;
+}
+
+/// Rules for masking or hashing field values by key.
+///
+/// A `Redaction` is applied by the [`ForestLayer`] as fields are recorded on
+/// spans and events, so redacted values never reach the processor, the
+/// [immediate processor], or logs returned by [`capture`].
+///
+/// Keys are matched against glob patterns, where `*` matches any sequence of
+/// characters and `?` matches any single character, ignoring ASCII case. With
+/// the `regex` feature, keys can also be matched with regular expressions, and
+/// substrings of event messages can be masked.
+///
+/// Matching values are either masked, replacing them with `[redacted]`, or
+/// hashed, replacing them with a hash of the value like `<hash:1f2e3d4c5b6a7988>`.
+/// Hashing allows equal values to be correlated across logs without revealing
+/// them.
+///
+/// Values are hashed with SipHash-2-4 keyed by a caller-supplied secret, which
+/// is stable across Rust versions and platforms. The key is what makes this
+/// redaction: anyone can compute an unkeyed hash of a guessed value, like an
+/// email address or a short number, and compare it to the logs. Keep the key
+/// out of the logs and the source code, and use the same key wherever hashes
+/// should be correlated.
+///
+/// If several rules match a key, the first one added applies.
+///
+/// [`ForestLayer`]: crate::ForestLayer
+/// [immediate processor]: crate::ForestLayer::immediate
+/// [`capture`]: crate::capture
+///
+/// # Examples
+///
+/// ```
+/// use tracing_forest::redact::Redaction;
+/// use tracing_forest::ForestLayer;
+///
+/// # let key_from_config = || [0; 16];
+/// let key: [u8; 16] = key_from_config();
+///
+/// let redaction = Redaction::new()
+///     .mask("authorization")
+///     .mask("*_token")
+///     .hash_with_key("email", key);
+///
+/// let layer = ForestLayer::default().redact(redaction);
+/// # let _ = layer;
+/// ```
+#[derive(Clone, Debug)]
+pub struct Redaction {
+    rules: Vec<(Pattern, Action)>,
+    #[cfg(feature = "regex")]
+    messages: Vec<Regex>,
+    mask: Cow<'static, str>,
+}
+
+#[derive(Clone, Debug)]
+enum Pattern {
+    Glob(String),
+    #[cfg(feature = "regex")]
+    Regex(Regex),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Action {
+    Mask,
+    Hash(Key),
+}
+
+/// A secret hashing key, which isn't shown by `Debug`.
+#[derive(Clone, Copy)]
+struct Key([u8; 16]);
+
+impl Redaction {
+    /// Returns a new `Redaction` without any rules.
+    pub fn new() -> Self {
+        Redaction {
+            rules: Vec::new(),
+            #[cfg(feature = "regex")]
+            messages: Vec::new(),
+            mask: Cow::Borrowed("[redacted]"),
+        }
+    }
+
+    /// Mask the values of fields whose keys match a glob pattern.
+    pub fn mask(self, pattern: impl Into<String>) -> Self {
+        self.rule(Pattern::Glob(pattern.into()), Action::Mask)
+    }
+
+    /// Hash the values of fields whose keys match a glob pattern, using a
+    /// secret key.
+    pub fn hash_with_key(self, pattern: impl Into<String>, key: [u8; 16]) -> Self {
+        self.rule(Pattern::Glob(pattern.into()), Action::Hash(Key(key)))
+    }
+
+    /// Set the text that masked values are replaced with. Defaults to
+    /// `[redacted]`.
+    pub fn mask_with(mut self, mask: impl Into<Cow<'static, str>>) -> Self {
+        self.mask = mask.into();
+        self
+    }
+
+    /// Returns whether there are no rules.
+    pub fn is_empty(&self) -> bool {
+        #[cfg(feature = "regex")]
+        if !self.messages.is_empty() {
+            return false;
+        }
+
+        self.rules.is_empty()
+    }
+
+    fn rule(mut self, pattern: Pattern, action: Action) -> Self {
+        self.rules.push((pattern, action));
+        self
+    }
+
+    /// Returns the value to record for a field.
+    pub(crate) fn field(&self, key: &str, value: String) -> String {
+        let action = self.rules.iter().find_map(|(pattern, action)| {
+            let matched = match pattern {
+                Pattern::Glob(glob) => glob_matches(glob, key),
+                #[cfg(feature = "regex")]
+                Pattern::Regex(regex) => regex.is_match(key),
+            };
+            matched.then_some(*action)
+        });
+
+        match action {
+            None => value,
+            Some(Action::Mask) => self.mask.to_string(),
+            Some(Action::Hash(key)) => format!("<hash:{:016x}>", siphash(&key.0, value.as_bytes())),
+        }
+    }
+
+    /// Returns the message to record for an event.
+    #[cfg(feature = "regex")]
+    pub(crate) fn message(&self, message: String) -> String {
+        let mut message = message;
+        for regex in &self.messages {
+            if let Cow::Owned(replaced) = regex.replace_all(&message, regex::NoExpand(&self.mask)) {
+                message = replaced;
+            }
+        }
+        message
+    }
+
+    /// Returns the message to record for an event.
+    #[cfg(not(feature = "regex"))]
+    pub(crate) fn message(&self, message: String) -> String {
+        message
+    }
+}
+
+cfg_regex! {
+    impl Redaction {
+        /// Mask the values of fields whose keys match a regular expression.
+        pub fn mask_regex(self, regex: Regex) -> Self {
+            self.rule(Pattern::Regex(regex), Action::Mask)
+        }
+
+        /// Hash the values of fields whose keys match a regular expression,
+        /// using a secret key.
+        pub fn hash_regex_with_key(self, regex: Regex, key: [u8; 16]) -> Self {
+            self.rule(Pattern::Regex(regex), Action::Hash(Key(key)))
+        }
+
+        /// Mask substrings of event messages that match a regular expression.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use regex::Regex;
+        /// use tracing_forest::redact::Redaction;
+        ///
+        /// let bearer = Regex::new(r"Bearer [A-Za-z0-9._~+/-]+=*").unwrap();
+        /// let redaction = Redaction::new().mask_messages(bearer);
+        /// # let _ = redaction;
+        /// ```
+        pub fn mask_messages(mut self, regex: Regex) -> Self {
+            self.messages.push(regex);
+            self
+        }
+    }
+}
+
+impl Default for Redaction {
+    fn default() -> Self {
+        Redaction::new()
+    }
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Key(..)")
+    }
+}
+
+/// Returns the SipHash-2-4 of some bytes with a 128-bit key.
+fn siphash(key: &[u8; 16], bytes: &[u8]) -> u64 {
+    let k0 = u64::from_le_bytes(key[..8].try_into().unwrap());
+    let k1 = u64::from_le_bytes(key[8..].try_into().unwrap());
+    let mut v = [
+        k0 ^ 0x736f_6d65_7073_6575,
+        k1 ^ 0x646f_7261_6e64_6f6d,
+        k0 ^ 0x6c79_6765_6e65_7261,
+        k1 ^ 0x7465_6462_7974_6573,
+    ];
+
+    let compress = |v: &mut [u64; 4], m: u64| {
+        v[3] ^= m;
+        sip_round(v);
+        sip_round(v);
+        v[0] ^= m;
+    };
+
+    let mut chunks = bytes.chunks_exact(8);
+    for chunk in &mut chunks {
+        compress(&mut v, u64::from_le_bytes(chunk.try_into().unwrap()));
+    }
+
+    // The last block holds the remaining bytes, and the length in its top byte.
+    let mut last = [0; 8];
+    last[..chunks.remainder().len()].copy_from_slice(chunks.remainder());
+    compress(
+        &mut v,
+        u64::from_le_bytes(last) | (bytes.len() as u64) << 56,
+    );
+
+    v[2] ^= 0xff;
+    for _ in 0..4 {
+        sip_round(&mut v);
+    }
+    v[0] ^ v[1] ^ v[2] ^ v[3]
+}
+
+fn sip_round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13) ^ v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16) ^ v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21) ^ v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17) ^ v[2];
+    v[2] = v[2].rotate_left(32);
+}
+
+/// Returns whether a key matches a glob pattern, ignoring ASCII case.
+fn glob_matches(pattern: &str, key: &str) -> bool {
+    // The pattern after the last `*`, and the key from where the `*` started
+    // matching, for backtracking.
+    let mut star: Option<(&str, &str)> = None;
+    let (mut p, mut k) = (pattern, key);
+
+    while let Some(key_char) = k.chars().next() {
+        let mut pattern_chars = p.chars();
+        match pattern_chars.next() {
+            Some('*') => {
+                star = Some((pattern_chars.as_str(), k));
+                p = pattern_chars.as_str();
+            }
+            Some(c) if c == '?' || c.eq_ignore_ascii_case(&key_char) => {
+                p = pattern_chars.as_str();
+                k = &k[key_char.len_utf8()..];
+            }
+            _ => match star {
+                Some((star_p, star_k)) => {
+                    // Let the `*` match one more character, and try again.
+                    let skipped = star_k.chars().next().map_or(0, char::len_utf8);
+                    star = Some((star_p, &star_k[skipped..]));
+                    p = star_p;
+                    k = &star_k[skipped..];
+                }
+                None => return false,
+            },
+        }
+    }
+
+    p.chars().all(|c| c == '*')
+}
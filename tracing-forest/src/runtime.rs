@@ -101,12 +101,15 @@
 //! [`tree` module-level documentation](crate::tree)
 //! 
 //! For full configuration options, see the [`Builder`] documentation.
+use crate::immediate::{ExplicitOnly, ImmediatePolicy};
 use crate::layer::ForestLayer;
-use crate::printer::PrettyPrinter;
+use crate::printer::{Immediate, ImmediatePrinter, MakeStderr, PrettyPrinter, Printer};
 use crate::tree::Tree;
 use crate::fail;
 use crate::tag::{TagParser, NoTag};
-use crate::processor::{self, Processor, WithFallback};
+use crate::processor::{self, AsyncProcessor, Processor, WithFallback};
+use crate::limit::Limits;
+use crate::redact::Redaction;
 use std::future::Future;
 use std::iter;
 use tokio::sync::mpsc::{self, UnboundedReceiver};
@@ -115,6 +118,9 @@ use tracing::Subscriber;
 use tracing_subscriber::Registry;
 use tracing_subscriber::layer::{Layered, SubscriberExt as _};
 
+/// The subscriber a [`Builder`] installs: a [`ForestLayer`] over a [`Registry`].
+type ForestSubscriber<Tx, T, I, M> = Layered<ForestLayer<Tx, T, I, M>, Registry>;
+
 /// Begins the configuration of a `ForestLayer` subscriber that sends log trees
 /// to a processing task for formatting and writing.
 /// 
@@ -155,6 +161,7 @@ pub fn capture() -> Builder<InnerSender<impl Processor>, Capture, NoTag> {
     worker_task_inner(Capture(()), false)
 }
 
+#[allow(clippy::result_large_err)]
 fn worker_task_inner<P>(worker_processor: P, is_global: bool) -> Builder<InnerSender<impl Processor>, P, NoTag> {
     let (tx, rx) = mpsc::unbounded_channel();
 
@@ -172,6 +179,11 @@ fn worker_task_inner<P>(worker_processor: P, is_global: bool) -> Builder<InnerSe
         receiver: rx,
         tag: NoTag,
         is_global,
+        immediate: Printer::new().formatter(Immediate).writer(MakeStderr),
+        policy: ExplicitOnly,
+        redaction: Redaction::new(),
+        limits: Limits::new(),
+        chronological: false,
     }
 }
 
@@ -184,8 +196,11 @@ fn worker_task_inner<P>(worker_processor: P, is_global: bool) -> Builder<InnerSe
 /// Configuration options include:
 /// * Setting the [tag][set_tag].
 /// * Installing [globally][set_global].
+/// * Setting the [processor][immediate] and [policy][immediate_if] for
+///   immediate events.
 /// * Configuring the [internal sender][map_sender] with fallbacks.
-/// * Configuring the [processor][map_receiver] in the worker task.
+/// * Configuring the [processor][map_receiver] in the worker task, or replacing
+///   it with an [async processor][map_receiver_async].
 /// 
 /// To finish the `Runtime`, call the [`build`] method to compose the configured
 /// `ForestLayer` onto a [`Registry`]. Alternatively, the [`build_on`] method
@@ -195,16 +210,24 @@ fn worker_task_inner<P>(worker_processor: P, is_global: bool) -> Builder<InnerSe
 /// [builder]: https://rust-lang.github.io/api-guidelines/type-safety.html#builders-enable-construction-of-complex-values-c-builder
 /// [set_tag]: Builder::set_tag
 /// [set_global]: Builder::set_global
+/// [immediate]: Builder::immediate
+/// [immediate_if]: Builder::immediate_if
 /// [map_sender]: Builder::map_sender
 /// [map_receiver]: Builder::map_receiver
+/// [map_receiver_async]: Builder::map_receiver_async
 /// [`build`]: Builder::build
 /// [`build_on`]: Builder::build_on
-pub struct Builder<Tx, Rx, T> {
+pub struct Builder<Tx, Rx, T, I = ImmediatePrinter, M = ExplicitOnly> {
     sender_processor: Tx,
     worker_processor: Rx,
     receiver: UnboundedReceiver<Tree>,
     tag: T,
     is_global: bool,
+    immediate: I,
+    policy: M,
+    redaction: Redaction,
+    limits: Limits,
+    chronological: bool,
 }
 
 /// A marker type indicating that trace data should be captured for later use.
@@ -213,6 +236,9 @@ pub struct Capture(());
 /// A marker type indicating that trace data should be processed.
 pub struct WorkerTask<P>(P);
 
+/// A marker type indicating that trace data should be processed asynchronously.
+pub struct AsyncWorkerTask<P>(P);
+
 /// The [`Processor`] used within a `tracing-forest` subscriber for sending logs
 /// to a processing task.
 /// 
@@ -234,7 +260,7 @@ impl<P> sealed::Sealed for InnerSender<P> {}
 
 impl<S: sealed::Sealed, P> sealed::Sealed for WithFallback<S, P> {}
 
-impl<Tx, P, T> Builder<Tx, WorkerTask<P>, T>
+impl<Tx, P, T, I, M> Builder<Tx, WorkerTask<P>, T, I, M>
 where
     P: Processor,
 {
@@ -271,7 +297,7 @@ where
     ///     .await;
     /// # }
     /// ```
-    pub fn map_receiver<F, P2>(self, f: F) -> Builder<Tx, WorkerTask<P2>, T>
+    pub fn map_receiver<F, P2>(self, f: F) -> Builder<Tx, WorkerTask<P2>, T, I, M>
     where
         F: FnOnce(P) -> P2,
         P2: Processor,
@@ -282,14 +308,71 @@ where
             receiver: self.receiver,
             tag: self.tag,
             is_global: self.is_global,
+            immediate: self.immediate,
+            policy: self.policy,
+            redaction: self.redaction,
+            limits: self.limits,
+            chronological: self.chronological,
+        }
+    }
+
+    /// Configure an [`AsyncProcessor`] on the receiving end of the log channel.
+    ///
+    /// This method accepts a closure that accepts the current [`Processor`] on the
+    /// worker task, and maps it to an [`AsyncProcessor`]. The worker task then
+    /// `.await`s the processing of each tree, which allows writing to async sinks
+    /// without blocking the runtime thread.
+    ///
+    /// Synchronous processors can be run on Tokio's blocking thread pool by
+    /// wrapping them with [`processor::blocking`].
+    ///
+    /// # Note
+    ///
+    /// This method is only available if called after [`worker_task`].
+    ///
+    /// # Examples
+    ///
+    /// Pretty printing on the blocking thread pool, or else falling back to stderr.
+    /// ```
+    /// use tracing_forest::{processor, traits::*};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     tracing_forest::worker_task()
+    ///         .map_receiver_async(|printer| processor::blocking(printer.or_stderr()))
+    ///         .build()
+    ///         .on(async {
+    ///             // ...
+    ///         })
+    ///         .await;
+    /// }
+    /// ```
+    pub fn map_receiver_async<F, P2>(self, f: F) -> Builder<Tx, AsyncWorkerTask<P2>, T, I, M>
+    where
+        F: FnOnce(P) -> P2,
+        P2: AsyncProcessor,
+    {
+        Builder {
+            sender_processor: self.sender_processor,
+            worker_processor: AsyncWorkerTask(f(self.worker_processor.0)),
+            receiver: self.receiver,
+            tag: self.tag,
+            is_global: self.is_global,
+            immediate: self.immediate,
+            policy: self.policy,
+            redaction: self.redaction,
+            limits: self.limits,
+            chronological: self.chronological,
         }
     }
 }
 
-impl<Tx, Rx, T> Builder<Tx, Rx, T>
+impl<Tx, Rx, T, I, M> Builder<Tx, Rx, T, I, M>
 where
     Tx: Processor + sealed::Sealed,
     T: TagParser,
+    I: Processor,
+    M: ImmediatePolicy,
 {
     /// Configure the processer within the subscriber that sends log trees to
     /// a processing task. This allows for dangling tasks to still generate trace
@@ -349,7 +432,7 @@ where
     ///     .await;
     /// # }
     /// ```
-    pub fn map_sender<F, Tx2>(self, f: F) -> Builder<Tx2, Rx, T>
+    pub fn map_sender<F, Tx2>(self, f: F) -> Builder<Tx2, Rx, T, I, M>
     where
         F: FnOnce(Tx) -> Tx2,
         Tx2: Processor + sealed::Sealed,
@@ -360,7 +443,11 @@ where
             receiver: self.receiver,
             tag: self.tag,
             is_global: self.is_global,
-
+            immediate: self.immediate,
+            policy: self.policy,
+            redaction: self.redaction,
+            limits: self.limits,
+            chronological: self.chronological,
         }
     }
 
@@ -387,7 +474,7 @@ where
     ///         .await;
     /// }
     /// ```
-    pub fn set_tag<T2>(self, tag: T2) -> Builder<Tx, Rx, T2>
+    pub fn set_tag<T2>(self, tag: T2) -> Builder<Tx, Rx, T2, I, M>
     where
         T2: TagParser,
     {
@@ -397,6 +484,11 @@ where
             receiver: self.receiver,
             tag,
             is_global: self.is_global,
+            immediate: self.immediate,
+            policy: self.policy,
+            redaction: self.redaction,
+            limits: self.limits,
+            chronological: self.chronological,
         }
     }
 
@@ -432,6 +524,132 @@ where
         self
     }
 
+    /// Set the [`Processor`] for events with `immediate = true`.
+    ///
+    /// Unlike the processor on the worker task, this processor runs as soon
+    /// as the event occurs, on the thread that emitted it.
+    ///
+    /// See [`ForestLayer::immediate`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use tracing_forest::printer::{Compact, MakeStderr};
+    /// use tracing_forest::{traits::*, util::*, Printer};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let compact = Printer::new().formatter(Compact::new()).writer(MakeStderr);
+    ///
+    ///     tracing_forest::worker_task()
+    ///         .immediate(compact.or_none())
+    ///         .build()
+    ///         .on(async {
+    ///             warn!(immediate = true, "disk almost full");
+    ///         })
+    ///         .await;
+    /// }
+    /// ```
+    pub fn immediate<I2>(self, immediate: I2) -> Builder<Tx, Rx, T, I2, M>
+    where
+        I2: Processor,
+    {
+        Builder {
+            sender_processor: self.sender_processor,
+            worker_processor: self.worker_processor,
+            receiver: self.receiver,
+            tag: self.tag,
+            is_global: self.is_global,
+            immediate,
+            policy: self.policy,
+            redaction: self.redaction,
+            limits: self.limits,
+            chronological: self.chronological,
+        }
+    }
+
+    /// Set an [`ImmediatePolicy`] deciding which events are processed
+    /// immediately, in addition to those with `immediate = true`.
+    ///
+    /// See [`ForestLayer::immediate_if`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use tracing_forest::{immediate, util::*};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     tracing_forest::worker_task()
+    ///         .immediate_if(immediate::at_least(Level::ERROR))
+    ///         .build()
+    ///         .on(async {
+    ///             // ...
+    ///         })
+    ///         .await;
+    /// }
+    /// ```
+    pub fn immediate_if<M2>(self, policy: M2) -> Builder<Tx, Rx, T, I, M2>
+    where
+        M2: ImmediatePolicy,
+    {
+        Builder {
+            sender_processor: self.sender_processor,
+            worker_processor: self.worker_processor,
+            receiver: self.receiver,
+            tag: self.tag,
+            is_global: self.is_global,
+            immediate: self.immediate,
+            policy,
+            redaction: self.redaction,
+            limits: self.limits,
+            chronological: self.chronological,
+        }
+    }
+
+    /// Set the [`Redaction`] applied to fields as they're recorded, so that
+    /// redacted values never reach the processor or captured logs.
+    ///
+    /// # Examples
+    /// ```
+    /// use tracing_forest::redact::Redaction;
+    /// use tracing_forest::util::*;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let logs = tracing_forest::capture()
+    ///         .redact(Redaction::new().mask("password"))
+    ///         .build()
+    ///         .on(async {
+    ///             info!(password = "hunter2", "logged in");
+    ///         })
+    ///         .await;
+    ///
+    ///     let event = logs[0].event().unwrap();
+    ///     assert_eq!(event.fields()[0].value(), "[redacted]");
+    /// }
+    /// ```
+    pub fn redact(mut self, redaction: Redaction) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    /// Set the [`Limits`] on the size of recorded data, keeping memory bounded
+    /// for large or busy trees.
+    ///
+    /// See the [`limit`](crate::limit) module for details.
+    pub fn limit(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Set whether the nodes of each span are ordered by when they started,
+    /// rather than when they completed.
+    ///
+    /// See [`ForestLayer::chronological`] for details.
+    pub fn chronological(mut self, chronological: bool) -> Self {
+        self.chronological = chronological;
+        self
+    }
+
     /// Finishes the `ForestLayer` by composing it into a [`Registry`], and
     /// returns it as a [`Runtime`].
     /// 
@@ -455,7 +673,7 @@ where
     ///         .await;
     /// }
     /// ```
-    pub fn build(self) -> Runtime<Layered<ForestLayer<Tx, T>, Registry>, Rx> {
+    pub fn build(self) -> Runtime<ForestSubscriber<Tx, T, I, M>, Rx> {
         self.build_on(|x| x)
     }
 
@@ -492,7 +710,7 @@ where
     /// ```
     pub fn build_on<F, S>(self, f: F) -> Runtime<S, Rx>
     where
-        F: FnOnce(Layered<ForestLayer<Tx, T>, Registry>) -> S,
+        F: FnOnce(ForestSubscriber<Tx, T, I, M>) -> S,
         S: Subscriber,
     {
         self.build_with(|layer| f(Registry::default().with(layer)))
@@ -536,10 +754,15 @@ where
     /// ```
     pub fn build_with<F, S>(self, f: F) -> Runtime<S, Rx>
     where
-        F: FnOnce(ForestLayer<Tx, T>) -> S,
+        F: FnOnce(ForestLayer<Tx, T, I, M>) -> S,
         S: Subscriber,
     {
-        let layer = ForestLayer::new(self.sender_processor, self.tag);
+        let layer = ForestLayer::new(self.sender_processor, self.tag)
+            .immediate(self.immediate)
+            .immediate_if(self.policy)
+            .redact(self.redaction)
+            .limit(self.limits)
+            .chronological(self.chronological);
         let subscriber = f(layer);
 
         Runtime {
@@ -556,7 +779,7 @@ where
 /// This type is returned by [`Builder::build`] and [`Builder::build_with`].
 pub struct Runtime<S, P> {
     subscriber: S,
-    worker_processor: P, // either `Process<_>` or `Capture`
+    worker_processor: P, // either `WorkerTask<_>`, `AsyncWorkerTask<_>`, or `Capture`
     receiver: UnboundedReceiver<Tree>,
     is_global: bool,
 }
@@ -568,45 +791,85 @@ where
 {
     /// Execute a future in the context of the configured subscriber.
     pub async fn on<F: Future>(self, f: F) -> F::Output {
-        let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
-        let processor = self.worker_processor.0;
-        let mut receiver = self.receiver;
+        run(self, f).await
+    }
+}
 
-        let handle = tokio::spawn(async move {
-            loop {
-                tokio::select! {
-                    Some(tree) = receiver.recv() => processor.process(tree).expect(fail::PROCESSING_ERROR),
-                    Ok(()) = &mut shutdown_rx => break,
-                    else => break,
-                }
-            }
+impl<S, P> Runtime<S, AsyncWorkerTask<P>>
+where
+    S: Subscriber + Send + Sync,
+    P: AsyncProcessor,
+{
+    /// Execute a future in the context of the configured subscriber.
+    pub async fn on<F: Future>(self, f: F) -> F::Output {
+        run(self, f).await
+    }
+}
 
-            receiver.close();
+/// A processor that runs on the worker task, shared by [`WorkerTask`] and
+/// [`AsyncWorkerTask`].
+trait Worker: 'static + Send {
+    fn process(&self, tree: Tree) -> processor::BoxFuture<'_>;
+}
 
-            // Drain any remaining logs in the channel buffer.
-            while let Ok(tree) = receiver.try_recv() {
-                processor.process(tree).expect(fail::PROCESSING_ERROR);
+impl<P: Processor + Send> Worker for WorkerTask<P> {
+    fn process(&self, tree: Tree) -> processor::BoxFuture<'_> {
+        Box::pin(std::future::ready(self.0.process(tree)))
+    }
+}
+
+impl<P: AsyncProcessor> Worker for AsyncWorkerTask<P> {
+    fn process(&self, tree: Tree) -> processor::BoxFuture<'_> {
+        self.0.process(tree)
+    }
+}
+
+/// Executes a future in the context of the runtime's subscriber, while its
+/// worker processes logs on a separate task.
+async fn run<S, W, F>(runtime: Runtime<S, W>, f: F) -> F::Output
+where
+    S: Subscriber + Send + Sync,
+    W: Worker,
+    F: Future,
+{
+    let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+    let worker = runtime.worker_processor;
+    let mut receiver = runtime.receiver;
+
+    let handle = tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                Some(tree) = receiver.recv() => worker.process(tree).await.expect(fail::PROCESSING_ERROR),
+                Ok(()) = &mut shutdown_rx => break,
+                else => break,
             }
-        });
+        }
 
-        let output = {
-            let _guard = if self.is_global {
-                tracing::subscriber::set_global_default(self.subscriber)
-                    .expect("global default already set");
-                None
-            } else {
-                Some(tracing::subscriber::set_default(self.subscriber))
-            };
+        receiver.close();
 
-            f.await
+        // Drain any remaining logs in the channel buffer.
+        while let Ok(tree) = receiver.try_recv() {
+            worker.process(tree).await.expect(fail::PROCESSING_ERROR);
+        }
+    });
+
+    let output = {
+        let _guard = if runtime.is_global {
+            tracing::subscriber::set_global_default(runtime.subscriber)
+                .expect("global default already set");
+            None
+        } else {
+            Some(tracing::subscriber::set_default(runtime.subscriber))
         };
 
-        shutdown_tx.send(()).expect("Shutdown signal couldn't send, this is a bug");
+        f.await
+    };
 
-        handle.await.expect("Failed to join the writing task, this is a bug");
+    shutdown_tx.send(()).expect("Shutdown signal couldn't send, this is a bug");
 
-        output
-    }
+    handle.await.expect("Failed to join the writing task, this is a bug");
+
+    output
 }
 
 impl<S> Runtime<S, Capture>
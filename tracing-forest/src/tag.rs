@@ -72,22 +72,23 @@
 //! INFO     ｉ [info]: no tags here
 //! ```
 use crate::cfg_serde;
+use std::borrow::Cow;
 use std::fmt;
 use tracing::{Event, Level};
 
-/// A basic `Copy` type containing information about where an event occurred.
+/// A basic type containing information about where an event occurred.
 ///
 /// See the [module-level documentation](mod@crate::tag) for more details.
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct Tag {
     /// Optional prefix for the tag message
-    prefix: Option<&'static str>,
+    prefix: Option<Cow<'static, str>>,
 
     /// Level specifying the importance of the log.
     ///
     /// This value isn't necessarily "trace", "debug", "info", "warn", or "error",
     /// and can be customized.
-    suffix: &'static str,
+    suffix: Cow<'static, str>,
 
     /// An icon, typically emoji, that represents the tag.
     icon: char,
@@ -116,13 +117,13 @@ impl Tag {
     }
 
     /// Returns the prefix, if there is one.
-    pub const fn prefix(&self) -> Option<&'static str> {
-        self.prefix
+    pub fn prefix(&self) -> Option<&str> {
+        self.prefix.as_deref()
     }
 
     /// Returns the suffix.
-    pub const fn suffix(&self) -> &'static str {
-        self.suffix
+    pub fn suffix(&self) -> &str {
+        &self.suffix
     }
 
     /// Returns the icon.
@@ -134,16 +135,16 @@ impl Tag {
 /// Incrementally construct [`Tag`]s.
 ///
 /// See [`Tag::builder`] for more details.
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct Builder<S, I> {
-    prefix: Option<&'static str>,
+    prefix: Option<Cow<'static, str>>,
     suffix: S,
     icon: I,
 }
 
 /// A type used by [`Builder`] to indicate that the suffix has been set.
-#[derive(Copy, Clone, PartialEq, Eq)]
-pub struct Suffix(&'static str);
+#[derive(Clone, PartialEq, Eq)]
+pub struct Suffix(Cow<'static, str>);
 
 /// A type used by [`Builder`] to indicate that the icon has been set.
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -151,18 +152,18 @@ pub struct Icon(char);
 
 impl<S, I> Builder<S, I> {
     /// Set the prefix.
-    pub fn prefix(self, prefix: &'static str) -> Builder<S, I> {
+    pub fn prefix(self, prefix: impl Into<Cow<'static, str>>) -> Builder<S, I> {
         Builder {
-            prefix: Some(prefix),
+            prefix: Some(prefix.into()),
             ..self
         }
     }
 
     /// Set the suffix.
-    pub fn suffix(self, suffix: &'static str) -> Builder<Suffix, I> {
+    pub fn suffix(self, suffix: impl Into<Cow<'static, str>>) -> Builder<Suffix, I> {
         Builder {
             prefix: self.prefix,
-            suffix: Suffix(suffix),
+            suffix: Suffix(suffix.into()),
             icon: self.icon,
         }
     }
@@ -191,7 +192,7 @@ impl<S, I> Builder<S, I> {
 
         Builder {
             prefix: self.prefix,
-            suffix: Suffix(suffix),
+            suffix: Suffix(suffix.into()),
             icon: Icon(icon),
         }
     }
@@ -214,7 +215,7 @@ impl Builder<Suffix, Icon> {
 
 impl fmt::Display for Tag {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if let Some(prefix) = self.prefix {
+        if let Some(prefix) = &self.prefix {
             write!(f, "{}.{}", prefix, self.suffix)
         } else {
             self.suffix.fmt(f)
@@ -229,7 +230,7 @@ impl From<Level> for Tag {
 }
 
 cfg_serde! {
-    use serde::{Serialize, Serializer};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
     impl Serialize for Tag {
         fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
@@ -237,6 +238,33 @@ cfg_serde! {
             serializer.serialize_str(&self.to_string())
         }
     }
+
+    impl<'de> Deserialize<'de> for Tag {
+        /// Deserializes a `Tag` from its `prefix.suffix` representation.
+        ///
+        /// Icons aren't serialized, so tags with a level as their suffix get that
+        /// level's icon, and other tags get a generic icon.
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let s = String::deserialize(deserializer)?;
+            let (prefix, suffix) = match s.rsplit_once('.') {
+                Some((prefix, suffix)) => (Some(prefix.to_owned()), suffix),
+                None => (None, s.as_str()),
+            };
+
+            let builder = Tag::builder();
+            let builder = match prefix {
+                Some(prefix) => builder.prefix(prefix),
+                None => builder,
+            };
+
+            Ok(match suffix.parse::<Level>() {
+                Ok(level) if level.as_str().eq_ignore_ascii_case(suffix) => {
+                    builder.level(level).build()
+                }
+                _ => builder.suffix(suffix.to_owned()).icon('🏷').build(),
+            })
+        }
+    }
 }
 
 /// A type that can parse [`Tag`]s from Tracing events.
@@ -0,0 +1,49 @@
+use crate::tree::{Field, FieldSet};
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+use serde::de::{Deserialize, Deserializer, Error, MapAccess, Visitor};
+use std::fmt;
+use std::time::Duration;
+use tracing::Level;
+
+pub(super) fn level<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Level, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    s.parse()
+        .map_err(|_| D::Error::custom(format!("invalid level: {}", s)))
+}
+
+pub(super) fn nanos<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    u64::deserialize(deserializer).map(Duration::from_nanos)
+}
+
+pub(super) fn fields<'de, D: Deserializer<'de>>(deserializer: D) -> Result<FieldSet, D::Error> {
+    struct FieldsVisitor;
+
+    impl<'de> Visitor<'de> for FieldsVisitor {
+        type Value = FieldSet;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a map of field names to values")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<FieldSet, A::Error> {
+            let mut fields = FieldSet::default();
+            while let Some((key, value)) = map.next_entry::<String, String>()? {
+                fields.push(Field::new(key, value));
+            }
+            Ok(fields)
+        }
+    }
+
+    deserializer.deserialize_map(FieldsVisitor)
+}
+
+#[cfg(feature = "chrono")]
+pub(super) fn timestamp<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<DateTime<Utc>, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    DateTime::parse_from_rfc3339(&s)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(D::Error::custom)
+}
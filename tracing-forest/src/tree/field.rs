@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 #[cfg(feature = "smallvec")]
 pub(crate) type FieldSet = smallvec::SmallVec<[Field; 3]>;
 #[cfg(not(feature = "smallvec"))]
@@ -6,18 +8,21 @@ pub(crate) type FieldSet = Vec<Field>;
 /// A key-value pair recorded from trace data.
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Field {
-    key: &'static str,
+    key: Cow<'static, str>,
     value: String,
 }
 
 impl Field {
-    pub(crate) fn new(key: &'static str, value: String) -> Self {
-        Field { key, value }
+    pub(crate) fn new(key: impl Into<Cow<'static, str>>, value: String) -> Self {
+        Field {
+            key: key.into(),
+            value,
+        }
     }
 
     /// Returns the field's key.
-    pub fn key(&self) -> &'static str {
-        self.key
+    pub fn key(&self) -> &str {
+        &self.key
     }
 
     /// Returns the field's value.
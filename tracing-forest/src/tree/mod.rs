@@ -4,17 +4,21 @@
 //! It consists of three types: [`Tree`], [`Span`], and [`Event`].
 //!
 //! [`capture`]: crate::runtime::capture
+use crate::cfg_cpu_time;
 use crate::tag::Tag;
 #[cfg(feature = "chrono")]
 use chrono::{DateTime, Utc};
 #[cfg(feature = "serde")]
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::time::Duration;
 use thiserror::Error;
 use tracing::Level;
 #[cfg(feature = "uuid")]
 use uuid::Uuid;
 
+#[cfg(feature = "serde")]
+pub(crate) mod de;
 mod field;
 #[cfg(feature = "serde")]
 mod ser;
@@ -32,7 +36,7 @@ pub(crate) use field::FieldSet;
 /// [`span`]: Tree::span
 /// [`capture`]: crate::runtime::capture
 #[derive(Clone, Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[allow(clippy::large_enum_variant)] // https://github.com/rust-lang/rust-clippy/issues/9798
 pub enum Tree {
     /// An [`Event`] leaf node.
@@ -44,7 +48,7 @@ pub enum Tree {
 
 /// A leaf node in the log tree carrying information about a Tracing event.
 #[derive(Clone, Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Event {
     /// Shared fields between events and spans.
     #[cfg_attr(feature = "serde", serde(flatten))]
@@ -59,51 +63,157 @@ pub struct Event {
 
 /// An internal node in the log tree carrying information about a Tracing span.
 #[derive(Clone, Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Span {
     /// Shared fields between events and spans.
     #[cfg_attr(feature = "serde", serde(flatten))]
     pub(crate) shared: Shared,
 
     /// The name of the span.
-    pub(crate) name: &'static str,
+    pub(crate) name: Cow<'static, str>,
 
     /// The total duration the span was open for.
     #[cfg_attr(
         feature = "serde",
-        serde(rename = "nanos_total", serialize_with = "ser::nanos")
+        serde(
+            rename = "nanos_total",
+            serialize_with = "ser::nanos",
+            deserialize_with = "de::nanos"
+        )
     )]
     pub(crate) total_duration: Duration,
 
     /// The total duration inner spans were open for.
     #[cfg_attr(
         feature = "serde",
-        serde(rename = "nanos_nested", serialize_with = "ser::nanos")
+        serde(
+            rename = "nanos_nested",
+            serialize_with = "ser::nanos",
+            deserialize_with = "de::nanos"
+        )
     )]
     pub(crate) inner_duration: Duration,
 
+    /// When the span closed.
+    #[cfg(feature = "chrono")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            serialize_with = "ser::timestamp",
+            deserialize_with = "de::timestamp"
+        )
+    )]
+    pub(crate) closed_at: DateTime<Utc>,
+
+    /// The wall-clock duration between the span opening and closing.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            rename = "nanos_lifetime",
+            serialize_with = "ser::nanos",
+            deserialize_with = "de::nanos"
+        )
+    )]
+    pub(crate) lifetime: Duration,
+
+    /// The number of times the span was entered.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) entries: u64,
+
+    /// The longest duration the span was entered for at once.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            rename = "nanos_max_entry",
+            serialize_with = "ser::nanos",
+            deserialize_with = "de::nanos"
+        )
+    )]
+    pub(crate) max_entry_duration: Duration,
+
+    /// The CPU time used by the span's thread while the span was entered.
+    #[cfg(all(feature = "cpu-time", target_os = "linux"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            rename = "nanos_cpu",
+            serialize_with = "ser::nanos",
+            deserialize_with = "de::nanos"
+        )
+    )]
+    pub(crate) cpu_time: Duration,
+
+    /// The number of heap allocations made while the span was entered.
+    #[cfg(feature = "alloc-count")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) alloc_count: u64,
+
+    /// The number of bytes allocated while the span was entered.
+    #[cfg(feature = "alloc-count")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) alloc_bytes: u64,
+
+    /// The number of heap allocations made in the span, but not in child spans.
+    #[cfg(feature = "alloc-count")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) self_alloc_count: u64,
+
+    /// The number of bytes allocated in the span, but not in child spans.
+    #[cfg(feature = "alloc-count")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) self_alloc_bytes: u64,
+
+    /// The ID of this span alone, unlike `shared.uuid`, which is shared by
+    /// every span in its tree.
+    #[cfg(feature = "uuid")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) id: Uuid,
+
+    /// The IDs of the spans that this span follows from.
+    #[cfg(feature = "uuid")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) follows_from: Vec<Uuid>,
+
     /// Events and spans collected while the span was open.
     pub(crate) nodes: Vec<Tree>,
 }
 
 #[derive(Clone, Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub(crate) struct Shared {
     /// The ID of the event or span.
     #[cfg(feature = "uuid")]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub(crate) uuid: Uuid,
 
     /// When the event occurred or when the span opened.
     #[cfg(feature = "chrono")]
-    #[cfg_attr(feature = "serde", serde(serialize_with = "ser::timestamp"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            serialize_with = "ser::timestamp",
+            deserialize_with = "de::timestamp"
+        )
+    )]
     pub(crate) timestamp: DateTime<Utc>,
 
     /// The level the event or span occurred at.
-    #[cfg_attr(feature = "serde", serde(serialize_with = "ser::level"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "ser::level", deserialize_with = "de::level")
+    )]
     pub(crate) level: Level,
 
     /// Key-value data.
-    #[cfg_attr(feature = "serde", serde(serialize_with = "ser::fields"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "ser::fields", deserialize_with = "de::fields")
+    )]
     pub(crate) fields: FieldSet,
 }
 
@@ -227,7 +337,7 @@ impl Event {
 
     /// Returns the event's [`Tag`], if there is one.
     pub fn tag(&self) -> Option<Tag> {
-        self.tag
+        self.tag.clone()
     }
 
     /// Returns the event's fields.
@@ -237,12 +347,33 @@ impl Event {
 }
 
 impl Span {
-    pub(crate) fn new(shared: Shared, name: &'static str) -> Self {
+    /// Returns a span with no children that hasn't been entered, closing when
+    /// it opens.
+    pub(crate) fn new(shared: Shared, name: impl Into<Cow<'static, str>>) -> Self {
         Span {
+            #[cfg(feature = "chrono")]
+            closed_at: shared.timestamp,
             shared,
-            name,
+            name: name.into(),
             total_duration: Duration::ZERO,
             inner_duration: Duration::ZERO,
+            lifetime: Duration::ZERO,
+            entries: 0,
+            max_entry_duration: Duration::ZERO,
+            #[cfg(all(feature = "cpu-time", target_os = "linux"))]
+            cpu_time: Duration::ZERO,
+            #[cfg(feature = "alloc-count")]
+            alloc_count: 0,
+            #[cfg(feature = "alloc-count")]
+            alloc_bytes: 0,
+            #[cfg(feature = "alloc-count")]
+            self_alloc_count: 0,
+            #[cfg(feature = "alloc-count")]
+            self_alloc_bytes: 0,
+            #[cfg(feature = "uuid")]
+            id: Uuid::new_v4(),
+            #[cfg(feature = "uuid")]
+            follows_from: Vec::new(),
             nodes: Vec::new(),
         }
     }
@@ -253,6 +384,16 @@ impl Span {
         self.shared.uuid
     }
 
+    /// Returns a [`Uuid`] that identifies this span alone.
+    ///
+    /// Unlike [`Span::uuid`], which is shared by every span in a tree, each
+    /// span gets its own ID. Spans deserialized from data without IDs have the
+    /// nil `Uuid`.
+    #[cfg(feature = "uuid")]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
     /// Returns the [`DateTime`] that the span occurred at.
     #[cfg(feature = "chrono")]
     pub fn timestamp(&self) -> DateTime<Utc> {
@@ -264,9 +405,24 @@ impl Span {
         self.shared.level
     }
 
+    /// Returns the span's fields.
+    pub fn fields(&self) -> &[Field] {
+        &self.shared.fields
+    }
+
     /// Returns the span's name.
     pub fn name(&self) -> &str {
-        self.name
+        &self.name
+    }
+
+    /// Returns the [IDs] of the spans that this span [follows from], like a
+    /// request that queued a job.
+    ///
+    /// [IDs]: Span::id
+    /// [follows from]: tracing::Span::follows_from
+    #[cfg(feature = "uuid")]
+    pub fn follows_from(&self) -> &[Uuid] {
+        &self.follows_from
     }
 
     /// Returns the span's child trees.
@@ -274,6 +430,14 @@ impl Span {
         &self.nodes
     }
 
+    /// Returns a mutable reference to the span's child trees.
+    ///
+    /// This is useful for transforming trees before they're formatted, such as
+    /// removing events. Note that the span's durations aren't updated.
+    pub fn nodes_mut(&mut self) -> &mut Vec<Tree> {
+        &mut self.nodes
+    }
+
     /// Returns the total duration the span was entered for.
     ///
     /// If the span was used to instrument a `Future`, this only accounts for the
@@ -292,4 +456,109 @@ impl Span {
     pub fn base_duration(&self) -> Duration {
         self.total_duration - self.inner_duration
     }
+
+    /// Returns the [`DateTime`] that the span opened at, which is the same as
+    /// its [`timestamp`](Span::timestamp).
+    #[cfg(feature = "chrono")]
+    pub fn opened_at(&self) -> DateTime<Utc> {
+        self.shared.timestamp
+    }
+
+    /// Returns the [`DateTime`] that the span closed at.
+    #[cfg(feature = "chrono")]
+    pub fn closed_at(&self) -> DateTime<Utc> {
+        self.closed_at
+    }
+
+    /// Returns the wall-clock duration the span was open for, whether or not
+    /// it was entered.
+    pub fn lifetime(&self) -> Duration {
+        self.lifetime
+    }
+
+    /// Returns the number of times the span was entered.
+    ///
+    /// If the span was used to instrument a `Future`, this is the number of
+    /// times the `Future` was polled.
+    pub fn entries(&self) -> u64 {
+        self.entries
+    }
+
+    /// Returns the longest duration the span was entered for at once.
+    ///
+    /// If the span was used to instrument a `Future`, this is the duration of
+    /// its longest poll. Long polls usually mean that blocking code is running
+    /// in an async task.
+    pub fn max_entry_duration(&self) -> Duration {
+        self.max_entry_duration
+    }
+
+    cfg_cpu_time! {
+        /// Returns the CPU time used by the span's thread while the span was
+        /// entered, including time in child spans.
+        ///
+        /// Unlike [`total_duration`], this excludes time the thread spent blocked,
+        /// like waiting on I/O or for a lock, or descheduled. A span with CPU time
+        /// close to its total duration is CPU-bound. This is only measured on Linux.
+        ///
+        /// [`total_duration`]: Span::total_duration
+        pub fn cpu_time(&self) -> Duration {
+            self.cpu_time
+        }
+    }
+
+    /// Returns the number of heap allocations made by the span's thread while
+    /// the span was entered, including in child spans.
+    ///
+    /// This is only counted when [`CountingAlloc`] is the global allocator,
+    /// and is zero otherwise.
+    ///
+    /// [`CountingAlloc`]: crate::alloc::CountingAlloc
+    #[cfg(feature = "alloc-count")]
+    pub fn alloc_count(&self) -> u64 {
+        self.alloc_count
+    }
+
+    /// Returns the number of bytes allocated by the span's thread while the
+    /// span was entered, including in child spans.
+    ///
+    /// See [`alloc_count`](Span::alloc_count) for details.
+    #[cfg(feature = "alloc-count")]
+    pub fn alloc_bytes(&self) -> u64 {
+        self.alloc_bytes
+    }
+
+    /// Returns the number of heap allocations made while the span was entered,
+    /// but not in any child spans.
+    ///
+    /// See [`alloc_count`](Span::alloc_count) for details.
+    #[cfg(feature = "alloc-count")]
+    pub fn self_alloc_count(&self) -> u64 {
+        self.self_alloc_count
+    }
+
+    /// Returns the number of bytes allocated while the span was entered, but
+    /// not in any child spans.
+    ///
+    /// See [`alloc_count`](Span::alloc_count) for details.
+    #[cfg(feature = "alloc-count")]
+    pub fn self_alloc_bytes(&self) -> u64 {
+        self.self_alloc_bytes
+    }
+
+    #[cfg(feature = "alloc-count")]
+    pub(crate) fn allocs(&self) -> crate::alloc::Allocs {
+        crate::alloc::Allocs {
+            count: self.alloc_count,
+            bytes: self.alloc_bytes,
+        }
+    }
+
+    /// Returns the duration the span was open, but not entered.
+    ///
+    /// For a span instrumenting a `Future`, this is roughly the time spent
+    /// waiting to be polled.
+    pub fn idle_duration(&self) -> Duration {
+        self.lifetime.saturating_sub(self.total_duration)
+    }
 }
@@ -0,0 +1,98 @@
+//! Tests for attributing heap allocations to spans.
+#![cfg(all(feature = "tokio", feature = "alloc-count"))]
+use std::hint::black_box;
+use tracing_forest::alloc::CountingAlloc;
+use tracing_forest::util::*;
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc::system();
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+fn allocate(count: usize, bytes: usize) {
+    for _ in 0..count {
+        black_box(vec![0u8; bytes]);
+    }
+}
+
+#[tokio::test]
+async fn test_alloc_counts() -> Result<()> {
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            info_span!("request").in_scope(|| {
+                allocate(10, 1000);
+                info_span!("parse").in_scope(|| allocate(100, 100));
+                info_span!("respond").in_scope(|| {});
+            });
+        })
+        .await;
+
+    let request = logs[0].span()?;
+    let parse = request.nodes()[0].span()?;
+    let respond = request.nodes()[1].span()?;
+
+    assert!(parse.alloc_count() >= 100);
+    assert!(parse.alloc_bytes() >= 100 * 100);
+    assert!(parse.self_alloc_count() == parse.alloc_count());
+
+    // Inclusive counts contain the child spans, and self counts don't.
+    assert!(request.alloc_count() >= 110);
+    assert!(request.alloc_bytes() >= 10 * 1000 + 100 * 100);
+    assert!(
+        request.self_alloc_count()
+            == request.alloc_count() - parse.alloc_count() - respond.alloc_count()
+    );
+    assert!(
+        request.self_alloc_bytes()
+            == request.alloc_bytes() - parse.alloc_bytes() - respond.alloc_bytes()
+    );
+    assert!(request.self_alloc_count() >= 10);
+    assert!(request.self_alloc_count() < 100);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_layer_allocs_not_counted() -> Result<()> {
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            info_span!("quiet").in_scope(|| {
+                // Recording these allocates, but only inside the layer.
+                info!(user = "alice", attempts = 3, "logged in");
+                warn!(reason = ?Some("timeout"), "retrying");
+            });
+        })
+        .await;
+
+    let quiet = logs[0].span()?;
+    assert!(quiet.nodes().len() == 2);
+    assert!(quiet.alloc_count() == 0);
+    assert!(quiet.alloc_bytes() == 0);
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test]
+async fn test_alloc_counts_serde() -> Result<()> {
+    use tracing_forest::tree::Tree;
+
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            info_span!("span").in_scope(|| allocate(3, 64));
+        })
+        .await;
+
+    let span = logs[0].span()?;
+    let json = serde_json::to_value(&logs[0])?;
+    assert!(json["Span"]["alloc_count"] == span.alloc_count());
+    assert!(json["Span"]["self_alloc_bytes"] == span.self_alloc_bytes());
+
+    let tree: Tree = serde_json::from_value(json)?;
+    assert!(tree.span()?.alloc_bytes() == span.alloc_bytes());
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+//! Tests for processing log trees with an `AsyncProcessor` in the worker task.
+#![cfg(feature = "tokio")]
+use std::sync::{Arc, Mutex};
+use tracing_forest::tree::Tree;
+use tracing_forest::util::*;
+use tracing_forest::{processor, AsyncProcessor, Processor};
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+struct Collect(Arc<Mutex<Vec<Tree>>>);
+
+impl Processor for Collect {
+    fn process(&self, tree: Tree) -> processor::Result {
+        self.0.lock().unwrap().push(tree);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_from_async_fn() -> Result<()> {
+    let logs = Arc::new(Mutex::new(Vec::<Tree>::new()));
+
+    let sink = logs.clone();
+    tracing_forest::worker_task()
+        .set_global(false)
+        .map_receiver_async(move |_| {
+            processor::from_async_fn(move |tree| {
+                let sink = sink.clone();
+                async move {
+                    tokio::task::yield_now().await;
+                    sink.lock().unwrap().push(tree);
+                    Ok(())
+                }
+            })
+        })
+        .build()
+        .on(async {
+            info!("first");
+            info_span!("my_span").in_scope(|| {
+                info!("second");
+            });
+        })
+        .await;
+
+    let logs = logs.lock().unwrap();
+    assert!(logs.len() == 2);
+    assert!(logs[0].event()?.message() == Some("first"));
+    assert!(logs[1].span()?.name() == "my_span");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_blocking() -> Result<()> {
+    let logs = Arc::new(Mutex::new(Vec::<Tree>::new()));
+
+    let sink = Collect(logs.clone());
+    tracing_forest::worker_task()
+        .set_global(false)
+        .map_receiver_async(move |_| processor::blocking(sink))
+        .build()
+        .on(async {
+            info!("hello from a blocking processor");
+        })
+        .await;
+
+    let logs = logs.lock().unwrap();
+    assert!(logs.len() == 1);
+    assert!(logs[0].event()?.message() == Some("hello from a blocking processor"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_or() -> Result<()> {
+    let logs = Arc::new(Mutex::new(Vec::<Tree>::new()));
+
+    let sink = Collect(logs.clone());
+    tracing_forest::worker_task()
+        .set_global(false)
+        .map_receiver_async(move |_| {
+            let failing = processor::from_async_fn(|tree| async move {
+                let err = std::io::Error::other("sink unavailable");
+                Err(processor::error(tree, err.into()))
+            });
+            failing.or(processor::blocking(sink))
+        })
+        .build()
+        .on(async {
+            info!("rescued by the fallback");
+        })
+        .await;
+
+    let logs = logs.lock().unwrap();
+    assert!(logs.len() == 1);
+    assert!(logs[0].event()?.message() == Some("rescued by the fallback"));
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+//! Tests for batching log trees with `Batch`.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing_forest::processor::batch::{self, Batch, BatchProcessor};
+use tracing_forest::tree::Tree;
+use tracing_forest::{traits::*, util::*};
+use tracing_subscriber::Registry;
+
+#[derive(Clone, Default)]
+struct Batches(Arc<Mutex<Vec<Vec<Tree>>>>);
+
+impl BatchProcessor for Batches {
+    fn process_batch(&self, trees: Vec<Tree>) -> batch::Result {
+        self.0.lock().unwrap().push(trees);
+        Ok(())
+    }
+}
+
+impl Batches {
+    fn sizes(&self) -> Vec<usize> {
+        self.0.lock().unwrap().iter().map(Vec::len).collect()
+    }
+}
+
+#[test]
+fn test_max_trees() {
+    let batches = Batches::default();
+    let layer = ForestLayer::from(Batch::builder(batches.clone()).max_trees(3).build());
+
+    tracing::subscriber::with_default(Registry::default().with(layer), || {
+        for i in 0..7 {
+            info!(i, "hello");
+        }
+        assert!(batches.sizes() == [3, 3]);
+    });
+
+    // The remaining tree is flushed when the layer is dropped.
+    assert!(batches.sizes() == [3, 3, 1]);
+}
+
+#[test]
+fn test_max_bytes() {
+    let batches = Batches::default();
+    let layer = ForestLayer::from(Batch::builder(batches.clone()).max_bytes(1).build());
+
+    tracing::subscriber::with_default(Registry::default().with(layer), || {
+        info!("first");
+        info!("second");
+    });
+
+    assert!(batches.sizes() == [1, 1]);
+}
+
+#[test]
+fn test_interval() {
+    let batches = Batches::default();
+    let layer = ForestLayer::from(
+        Batch::builder(batches.clone())
+            .interval(Duration::from_millis(50))
+            .build(),
+    );
+
+    tracing::subscriber::with_default(Registry::default().with(layer), || {
+        info!("first");
+        info!("second");
+        thread::sleep(Duration::from_millis(200));
+        assert!(batches.sizes() == [2]);
+    });
+}
+
+#[derive(Clone, Default)]
+struct Ordered {
+    writing: Arc<AtomicBool>,
+    overlapped: Arc<AtomicBool>,
+    written: Arc<Mutex<Vec<u64>>>,
+}
+
+impl BatchProcessor for Ordered {
+    fn process_batch(&self, trees: Vec<Tree>) -> batch::Result {
+        if self.writing.swap(true, Ordering::SeqCst) {
+            self.overlapped.store(true, Ordering::SeqCst);
+        }
+        thread::sleep(Duration::from_micros(500));
+        let mut written = self.written.lock().unwrap();
+        for tree in trees {
+            written.push(tree.event().unwrap().fields()[0].value().parse().unwrap());
+        }
+        drop(written);
+        self.writing.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_interval_and_size_flushes_are_serialized() {
+    let sink = Ordered::default();
+    let layer = ForestLayer::from(
+        Batch::builder(sink.clone())
+            .max_trees(4)
+            .interval(Duration::from_micros(100))
+            .build(),
+    );
+
+    tracing::subscriber::with_default(Registry::default().with(layer), || {
+        for i in 0..200u64 {
+            info!(i, "hello");
+            thread::sleep(Duration::from_micros(50));
+        }
+    });
+
+    assert!(!sink.overlapped.load(Ordering::SeqCst));
+    assert!(*sink.written.lock().unwrap() == (0..200).collect::<Vec<_>>());
+}
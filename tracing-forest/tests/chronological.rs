@@ -0,0 +1,58 @@
+//! Tests for ordering nodes by when they started.
+#![cfg(feature = "tokio")]
+use tracing_forest::tree::Tree;
+use tracing_forest::util::*;
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+fn names(tree: &Tree) -> Vec<String> {
+    match tree {
+        Tree::Event(event) => vec![event.message().unwrap_or_default().to_string()],
+        Tree::Span(span) => span
+            .nodes()
+            .iter()
+            .map(|node| match node {
+                Tree::Event(event) => event.message().unwrap_or_default().to_string(),
+                Tree::Span(span) => span.name().to_string(),
+            })
+            .collect(),
+    }
+}
+
+async fn interleaved(chronological: bool) -> Vec<Tree> {
+    tracing_forest::capture()
+        .chronological(chronological)
+        .build()
+        .on(async {
+            info_span!("request").in_scope(|| {
+                info!("start");
+                let first = info_span!("first");
+                let second = info_span!("second");
+                info!("both opened");
+                second.in_scope(|| info!("in second"));
+                drop(second);
+                first.in_scope(|| info!("in first"));
+                drop(first);
+                info!("done");
+            });
+        })
+        .await
+}
+
+#[tokio::test]
+async fn test_completion_order() -> Result<()> {
+    let logs = interleaved(false).await;
+    assert!(names(&logs[0]) == ["start", "both opened", "second", "first", "done"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_chronological_order() -> Result<()> {
+    let logs = interleaved(true).await;
+    assert!(names(&logs[0]) == ["start", "first", "second", "both opened", "done"]);
+
+    let first = logs[0].span()?.nodes()[1].span()?;
+    assert!(first.nodes()[0].event()?.message() == Some("in first"));
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+//! Tests for collapsing repeated nodes.
+#![cfg(feature = "tokio")]
+use std::time::Duration;
+use tracing_forest::collapse::Collapse;
+use tracing_forest::printer::{DurationDisplay, Formatter, Pretty};
+use tracing_forest::tree::Tree;
+use tracing_forest::util::*;
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+fn value<'a>(fields: &'a [tracing_forest::tree::Field], key: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|field| field.key() == key)
+        .map(|field| field.value())
+}
+
+async fn poll() -> Vec<Tree> {
+    tracing_forest::capture()
+        .build()
+        .on(async {
+            info_span!("poll").in_scope(|| {
+                info!("starting");
+                for i in 0..500 {
+                    info_span!("attempt", n = i, host = "db", min = 1).in_scope(|| {
+                        warn!("refused");
+                        std::thread::sleep(Duration::from_micros(10 * (i % 3)));
+                    });
+                }
+                info!(attempts = 500, "gave up");
+                info!(attempts = 500, "gave up");
+                info_span!("attempt", n = 500, host = "replica").in_scope(|| {
+                    info!("connected");
+                });
+            });
+        })
+        .await
+}
+
+#[tokio::test]
+async fn test_collapse() -> Result<()> {
+    let logs = poll().await;
+    let original = logs[0].span()?;
+    let tree = Collapse::new().apply(logs[0].clone());
+    let poll = tree.span()?;
+
+    let names: Vec<_> = poll
+        .nodes()
+        .iter()
+        .map(|node| match node {
+            Tree::Event(event) => event.message().unwrap().to_string(),
+            Tree::Span(span) => span.name().to_string(),
+        })
+        .collect();
+    // Only consecutive attempts are merged, so the last one stays after the
+    // events that happened before it.
+    assert!(names == ["starting", "attempt ×500", "gave up ×2", "attempt"]);
+
+    let attempt = poll.nodes()[1].span()?;
+    let attempts: Vec<_> = original.nodes()[1..501]
+        .iter()
+        .map(|node| node.span())
+        .collect::<core::result::Result<_, _>>()?;
+    let total: Duration = attempts.iter().map(|span| span.total_duration()).sum();
+    let min = attempts.iter().map(|span| span.total_duration()).min();
+    let max = attempts.iter().map(|span| span.total_duration()).max();
+    assert!(attempt.total_duration() == total);
+    let min = DurationDisplay(min.unwrap()).to_string();
+    let max = DurationDisplay(max.unwrap()).to_string();
+    assert!(value(attempt.fields(), "collapse.min") == Some(min.as_str()));
+    assert!(value(attempt.fields(), "collapse.max") == Some(max.as_str()));
+
+    // Only the fields every attempt had are kept, and they don't clash with
+    // the added ones.
+    assert!(value(attempt.fields(), "host") == Some("\"db\""));
+    assert!(value(attempt.fields(), "min") == Some("1"));
+    assert!(value(attempt.fields(), "n").is_none());
+
+    // The children of every attempt are merged too.
+    assert!(attempt.nodes().len() == 1);
+    let refused = attempt.nodes()[0].event()?;
+    assert!(refused.message() == Some("refused ×500"));
+    assert!(refused.level() == Level::WARN);
+    #[cfg(feature = "chrono")]
+    {
+        let first = attempts[0].nodes()[0].event()?.timestamp().to_rfc3339();
+        let last = attempts[499].nodes()[0].event()?.timestamp().to_rfc3339();
+        assert!(value(refused.fields(), "collapse.first") == Some(first.as_str()));
+        assert!(value(refused.fields(), "collapse.last") == Some(last.as_str()));
+    }
+
+    let last = poll.nodes()[3].span()?;
+    assert!(value(last.fields(), "host") == Some("\"replica\""));
+    assert!(last.nodes()[0].event()?.message() == Some("connected"));
+
+    assert!(poll.total_duration() == original.total_duration());
+    assert!(poll.inner_duration() == original.inner_duration());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_collapse_events_only() -> Result<()> {
+    let logs = poll().await;
+    let tree = Collapse::new().spans(false).apply(logs[0].clone());
+    let poll = tree.span()?;
+
+    // Each attempt has a single event, so only the events after them are merged.
+    assert!(poll.nodes().len() == 1 + 500 + 1 + 1);
+    let gave_up = poll.nodes()[501].event()?;
+    assert!(gave_up.message() == Some("gave up ×2"));
+    assert!(value(gave_up.fields(), "attempts") == Some("500"));
+
+    let tree = Collapse::new().events(false).apply(logs[0].clone());
+    assert!(tree.span()?.nodes().len() == 1 + 1 + 2 + 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_distinct_nodes_kept() -> Result<()> {
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            info_span!("request").in_scope(|| {
+                info!("same");
+                warn!("same");
+                info!(id = 1, "same");
+                info!(id = 2, "same");
+                info_span!("read").in_scope(|| {});
+                info_span!("write").in_scope(|| {});
+            });
+        })
+        .await;
+
+    let tree = Collapse::new().apply(logs[0].clone());
+    let request = tree.span()?;
+    assert!(request.nodes().len() == 6);
+    assert!(request.nodes()[4].span()?.name() == "read");
+    assert!(request.nodes()[4].span()?.fields().is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pretty_collapsed() -> Result<()> {
+    let logs = poll().await;
+    let pretty = Pretty.collapsed().fmt(&logs[0])?;
+
+    assert!(pretty.lines().count() == 7);
+    assert!(pretty.contains("refused ×500"));
+    assert!(pretty.contains("attempt ×500 ["));
+
+    // Formatting doesn't change the tree.
+    assert!(logs[0].span()?.nodes().len() == 504);
+
+    Ok(())
+}
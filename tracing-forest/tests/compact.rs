@@ -0,0 +1,75 @@
+//! Tests for the compact single-line formatter.
+#![cfg(feature = "tokio")]
+use tracing_forest::printer::{Compact, Formatter};
+use tracing_forest::tree::Tree;
+use tracing_forest::util::*;
+use tracing_forest::Tag;
+
+async fn checkout() -> Vec<Tree> {
+    tracing_forest::capture()
+        .set_tag(|event: &Event| {
+            (event.metadata().target() == "payment").then(|| {
+                Tag::builder()
+                    .prefix("payment")
+                    .suffix("error")
+                    .level(Level::ERROR)
+                    .build()
+            })
+        })
+        .build()
+        .on(async {
+            info_span!("checkout", user = "alice").in_scope(|| {
+                info_span!("payment").in_scope(|| {
+                    error!(target: "payment", code = 402, "card declined");
+                });
+                info!("multi\nline");
+            });
+        })
+        .await
+}
+
+/// Removes the uuid and timestamp from the start of a line.
+fn strip(line: &str) -> &str {
+    let mut line = line;
+    if cfg!(feature = "uuid") {
+        line = line.split_once(' ').unwrap().1;
+    }
+    if cfg!(feature = "chrono") {
+        line = line.split_once(' ').unwrap().1;
+    }
+    line
+}
+
+#[tokio::test]
+async fn test_compact() -> Result<(), Box<dyn std::error::Error>> {
+    let logs = checkout().await;
+    let compact = Compact::new().fmt(&logs[0])?;
+    let lines: Vec<&str> = compact.lines().map(strip).collect();
+
+    assert!(lines.len() == 4);
+    assert!(lines[0] == "ERROR    checkout > payment > [payment.error] card declined | code: 402");
+    assert!(lines[1].starts_with("INFO     checkout > payment [ "));
+    assert!(lines[1].ends_with("% ]"));
+    assert!(lines[2] == "INFO     checkout > multi\\nline");
+    assert!(lines[3].starts_with("INFO     checkout [ "));
+    assert!(lines[3].ends_with(" / 100.00% ] | user: \"alice\""));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_events_only() -> Result<(), Box<dyn std::error::Error>> {
+    let logs = checkout().await;
+    let compact = Compact::new().spans(false).fmt(&logs[0])?;
+    let lines: Vec<&str> = compact.lines().map(strip).collect();
+
+    assert!(
+        lines
+            == [
+                "ERROR    checkout > payment > [payment.error] card declined | code: 402",
+                "INFO     checkout > multi\\nline",
+            ]
+    );
+
+    Ok(())
+}
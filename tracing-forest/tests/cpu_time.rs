@@ -0,0 +1,72 @@
+//! Tests for recording the CPU time of spans.
+#![cfg(all(feature = "tokio", feature = "cpu-time", target_os = "linux"))]
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use tracing_forest::printer::{Formatter, Pretty, PrettyConfig};
+use tracing_forest::util::*;
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+fn spin(duration: Duration) {
+    let start = Instant::now();
+    let mut n = 0u64;
+    while start.elapsed() < duration {
+        n = black_box(n.wrapping_add(1));
+    }
+}
+
+#[tokio::test]
+async fn test_cpu_time() -> Result<()> {
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            info_span!("request").in_scope(|| {
+                info_span!("compute").in_scope(|| spin(Duration::from_millis(30)));
+                info_span!("wait").in_scope(|| std::thread::sleep(Duration::from_millis(30)));
+            });
+        })
+        .await;
+
+    let request = logs[0].span()?;
+    let compute = request.nodes()[0].span()?;
+    let wait = request.nodes()[1].span()?;
+
+    // Spinning is CPU-bound, and sleeping isn't.
+    assert!(compute.cpu_time() >= Duration::from_millis(15));
+    assert!(compute.cpu_time() <= compute.total_duration());
+    assert!(wait.cpu_time() < Duration::from_millis(15));
+    assert!(wait.total_duration() >= Duration::from_millis(30));
+
+    // CPU time includes child spans.
+    assert!(request.cpu_time() >= compute.cpu_time() + wait.cpu_time());
+
+    // CPU time is only shown when asked for.
+    assert!(!Pretty.fmt(&logs[0])?.contains(" cpu"));
+    let pretty = PrettyConfig::new().cpu_time(true).fmt(&logs[0])?;
+    assert!(pretty.contains(" cpu | "));
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test]
+async fn test_cpu_time_serde() -> Result<()> {
+    use tracing_forest::tree::Tree;
+
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            info_span!("compute").in_scope(|| spin(Duration::from_millis(5)));
+        })
+        .await;
+
+    let json = serde_json::to_value(&logs[0])?;
+    assert!(
+        json["Span"]["nanos_cpu"].as_u64() == Some(logs[0].span()?.cpu_time().as_nanos() as u64)
+    );
+
+    let tree: Tree = serde_json::from_value(json)?;
+    assert!(tree.span()?.cpu_time() == logs[0].span()?.cpu_time());
+
+    Ok(())
+}
@@ -0,0 +1,127 @@
+//! Tests for the CSV formatter.
+#![cfg(feature = "tokio")]
+use tracing_forest::printer::{Csv, Formatter};
+use tracing_forest::tree::Tree;
+use tracing_forest::util::*;
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+async fn checkout() -> Vec<Tree> {
+    tracing_forest::capture()
+        .build()
+        .on(async {
+            info_span!("checkout", user = "alice, \"al\"").in_scope(|| {
+                info_span!("db.query").in_scope(|| {
+                    error!(code = 402, "card declined, try again");
+                });
+            });
+            warn!("outside of any span");
+        })
+        .await
+}
+
+/// Splits a row into cells, unquoting them.
+fn cells(row: &str) -> Vec<String> {
+    let mut cells = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                cells.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => cells.push(String::new()),
+            c => cells.last_mut().unwrap().push(c),
+        }
+    }
+    cells
+}
+
+#[tokio::test]
+async fn test_spans() -> Result<()> {
+    let logs = checkout().await;
+    let csv = Csv::new().report(&logs);
+    let rows: Vec<&str> = csv.lines().collect();
+
+    assert!(rows[0] == Csv::header().trim_end());
+    assert!(rows.len() == 3);
+
+    let header = cells(rows[0]);
+    let column = |row: &[String], name: &str| {
+        let index = header.iter().position(|column| column == name).unwrap();
+        row[index].clone()
+    };
+
+    let checkout = cells(rows[1]);
+    assert!(checkout.len() == header.len());
+    assert!(column(&checkout, "kind") == "span");
+    assert!(column(&checkout, "path") == "checkout");
+    assert!(column(&checkout, "depth") == "0");
+    assert!(column(&checkout, "level") == "INFO");
+    assert!(!column(&checkout, "start").is_empty());
+
+    let fields: serde_json::Value = serde_json::from_str(&column(&checkout, "fields"))?;
+    assert!(fields["user"] == "\"alice, \\\"al\\\"\"");
+
+    let total: u128 = column(&checkout, "total_nanos").parse()?;
+    let base: u128 = column(&checkout, "base_nanos").parse()?;
+    let inner: u128 = column(&checkout, "inner_nanos").parse()?;
+    assert!(total == base + inner);
+
+    let query = cells(rows[2]);
+    assert!(column(&query, "path") == "checkout/db.query");
+    assert!(column(&query, "name") == "db.query");
+    assert!(column(&query, "depth") == "1");
+    assert!(column(&query, "fields") == "{}");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_events() -> Result<()> {
+    let logs = checkout().await;
+    let csv = Csv::new().events(true).report(&logs);
+    let rows: Vec<Vec<String>> = csv.lines().skip(1).map(cells).collect();
+
+    assert!(rows.len() == 4);
+    let kinds: Vec<&str> = rows.iter().map(|row| row[0].as_str()).collect();
+    assert!(kinds == ["span", "span", "event", "event"]);
+
+    let header = cells(Csv::header().trim_end());
+    let column = |row: &[String], name: &str| {
+        let index = header.iter().position(|column| column == name).unwrap();
+        row[index].clone()
+    };
+
+    let declined = &rows[2];
+    assert!(declined.len() == header.len());
+    assert!(column(declined, "path") == "checkout/db.query");
+    assert!(column(declined, "depth") == "2");
+    assert!(column(declined, "level") == "ERROR");
+    assert!(column(declined, "message") == "card declined, try again");
+    assert!(column(declined, "total_nanos").is_empty());
+    assert!(column(declined, "fields") == r#"{"code":"402"}"#);
+
+    let outside = &rows[3];
+    assert!(column(outside, "path").is_empty());
+    assert!(column(outside, "depth") == "0");
+    assert!(column(outside, "level") == "WARN");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_formatter() -> Result<()> {
+    let logs = checkout().await;
+
+    // Without a header, so rows can be appended to an existing file.
+    let csv = Csv::new().fmt(&logs[0])?;
+    assert!(csv.lines().count() == 2);
+    assert!(csv.starts_with("span,"));
+
+    assert!(Csv::new().fmt(&logs[1])?.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+//! Tests for comparing span durations between runs.
+#![cfg(feature = "serde")]
+use std::time::Duration;
+use tracing_forest::diff::Profile;
+use tracing_forest::tree::Tree;
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Builds a span tree from `(name, micros, children)`.
+fn span(name: &str, micros: u64, children: &[String]) -> String {
+    format!(
+        r#"{{"Span":{{"level":"INFO","fields":{{}},"name":"{}","nanos_total":{},"nanos_nested":0,"nodes":[{}]}}}}"#,
+        name,
+        micros * 1000,
+        children.join(",")
+    )
+}
+
+fn tree(json: String) -> Result<Tree> {
+    Ok(serde_json::from_str(&json)?)
+}
+
+fn run(query_micros: &[u64], extra: &str) -> Result<Profile> {
+    let mut trees = Vec::new();
+    for &micros in query_micros {
+        let query = span("query", micros, &[]);
+        let other = span(extra, 1, &[]);
+        trees.push(tree(span("request", micros + 10, &[query, other]))?);
+    }
+    Ok(trees.into_iter().collect())
+}
+
+#[test]
+fn test_stats() -> Result<()> {
+    let profile = run(&[10, 20, 30, 40, 50, 60, 70, 80, 90, 100], "cache")?;
+    let stats = profile.stats("request/query").unwrap();
+
+    assert!(stats.count == 10);
+    assert!(stats.mean == Duration::from_micros(55));
+    assert!(stats.min == Duration::from_micros(10));
+    assert!(stats.p50 == Duration::from_micros(50));
+    assert!(stats.p90 == Duration::from_micros(90));
+    assert!(stats.p99 == Duration::from_micros(100));
+    assert!(stats.max == Duration::from_micros(100));
+
+    let paths: Vec<&str> = profile.iter().map(|(path, _)| path).collect();
+    assert!(paths == ["request", "request/cache", "request/query"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_compare() -> Result<()> {
+    let before = run(&[100, 100, 100, 100], "cache")?;
+    let after = run(&[150, 150, 150, 150], "retry")?;
+
+    let diff = before.compare(&after);
+
+    assert!(diff.changes.len() == 2);
+    assert!(diff.removed.len() == 1 && diff.removed[0].0 == "request/cache");
+    assert!(diff.added.len() == 1 && diff.added[0].0 == "request/retry");
+
+    let regressions = diff.regressions(0.1);
+    assert!(regressions.len() == 2);
+    assert!(regressions[0].path == "request/query");
+    assert!((regressions[0].mean_change() - 0.5).abs() < 1e-9);
+    assert!((regressions[0].relative_change(|stats| stats.p99) - 0.5).abs() < 1e-9);
+    assert!(diff.improvements(0.1).is_empty());
+
+    let diff = after.compare(&before);
+    assert!(diff.regressions(0.1).is_empty());
+    assert!(diff.improvements(0.1)[0].path == "request/query");
+
+    Ok(())
+}
+
+#[test]
+fn test_events_ignored() -> Result<()> {
+    let event =
+        tree(r#"{"Event":{"level":"INFO","fields":{},"message":"hello","tag":null}}"#.to_string())?;
+    let profile: Profile = [event].iter().collect();
+    assert!(profile.is_empty());
+    Ok(())
+}
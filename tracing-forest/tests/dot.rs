@@ -0,0 +1,119 @@
+//! Tests for the Graphviz DOT formatter.
+#![cfg(feature = "tokio")]
+use tracing_forest::printer::{Dot, Formatter};
+use tracing_forest::util::*;
+
+#[tokio::test]
+async fn test_dot() -> Result<(), Box<dyn std::error::Error>> {
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            info_span!("checkout", user = "alice").in_scope(|| {
+                info_span!("payment").in_scope(|| {
+                    error!("card \"declined\"");
+                });
+                debug!("done");
+            });
+        })
+        .await;
+
+    let dot = Dot::new().fmt(&logs[0])?;
+
+    assert!(dot.starts_with("digraph forest {\n"));
+    assert!(dot.ends_with("}\n"));
+    assert!(!dot.contains("rankdir"));
+
+    // Spans are boxes labeled with their duration and percent of the root.
+    assert!(dot.contains("n0 [shape=box"));
+    assert!(dot.contains("label=\"checkout\\n"));
+    assert!(dot.contains("| 100.00%\\nuser: \\\"alice\\\"\""));
+    assert!(dot.contains("label=\"payment\\n"));
+
+    // Events are notes colored by level, with quotes escaped.
+    assert!(dot.contains("n2 [shape=note, style=filled, fillcolor=\"#fee2e2\""));
+    assert!(dot.contains("[error]: card \\\"declined\\\"\""));
+    assert!(dot.contains("n3 [shape=note, style=filled, fillcolor=\"#dbeafe\""));
+
+    // Edges follow the tree structure.
+    let edges: Vec<&str> = dot.lines().filter(|line| line.contains("->")).collect();
+    assert!(edges == ["    n1 -> n2;", "    n0 -> n1;", "    n0 -> n3;"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_report() -> Result<(), Box<dyn std::error::Error>> {
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            info_span!("first").in_scope(|| {});
+            info_span!("second").in_scope(|| {});
+        })
+        .await;
+
+    let dot = Dot::new().left_to_right(true).report(&logs);
+
+    assert!(dot.matches("digraph").count() == 1);
+    assert!(dot.contains("rankdir=LR;"));
+    assert!(dot.contains("n0 [shape=box"));
+    assert!(dot.contains("n1 [shape=box"));
+    assert!(!dot.contains("->"));
+
+    Ok(())
+}
+
+#[cfg(feature = "uuid")]
+#[tokio::test]
+async fn test_follows_from() -> Result<(), Box<dyn std::error::Error>> {
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            let job = info_span!(parent: None, "job");
+            info_span!("request").in_scope(|| {
+                let validate = info_span!("validate");
+                validate.in_scope(|| {});
+                let audit = info_span!("audit");
+                audit.follows_from(validate.id());
+                drop(validate);
+                audit.in_scope(|| {});
+                drop(audit);
+
+                let enqueue = info_span!("enqueue");
+                job.follows_from(enqueue.id());
+                enqueue.in_scope(|| {});
+            });
+            job.in_scope(|| info!("running"));
+        })
+        .await;
+
+    let request = logs[0].span()?;
+    let job = logs[1].span()?;
+    assert!(request.name() == "request" && job.name() == "job");
+    let validate = request.nodes()[0].span()?;
+    let audit = request.nodes()[1].span()?;
+    let enqueue = request.nodes()[2].span()?;
+
+    // Each span has its own ID, even though the tree shares a `Uuid`.
+    assert!(validate.uuid() == audit.uuid() && validate.id() != audit.id());
+    assert!(audit.follows_from() == [validate.id()]);
+    assert!(job.follows_from() == [enqueue.id()]);
+    assert!(request.follows_from().is_empty());
+
+    // Dashed edges go from the exact spans followed from, within a tree and
+    // across trees.
+    let dot = Dot::new().report(&logs);
+    let edges: Vec<&str> = dot.lines().filter(|line| line.contains("->")).collect();
+    assert!(
+        edges
+            == [
+                "    n0 -> n1;",
+                "    n0 -> n2;",
+                "    n0 -> n3;",
+                "    n4 -> n5;",
+                "    n1 -> n2 [style=dashed];",
+                "    n3 -> n4 [style=dashed];"
+            ]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+//! Tests for converting `tracing_subscriber::fmt` JSON logs into trees.
+#![cfg(feature = "fmt-json")]
+use std::io;
+use std::sync::{Arc, Mutex};
+use tracing_forest::fmt_json::{self, Converter};
+use tracing_forest::tree::Tree;
+use tracing_forest::util::*;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::MakeWriter;
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for Buffer {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// Runs `f` with a `fmt` JSON subscriber and returns its output.
+fn fmt_json(span_events: FmtSpan, f: impl FnOnce()) -> String {
+    let buffer = Buffer::default();
+    let subscriber = tracing_subscriber::fmt()
+        .json()
+        .with_span_list(true)
+        .with_thread_ids(true)
+        .with_span_events(span_events)
+        .with_max_level(LevelFilter::TRACE)
+        .with_writer(buffer.clone())
+        .finish();
+
+    tracing::subscriber::with_default(subscriber, f);
+
+    let output = buffer.0.lock().unwrap().clone();
+    String::from_utf8(output).unwrap()
+}
+
+fn requests() {
+    info!("starting");
+    for id in 0..2 {
+        info_span!("request", id).in_scope(|| {
+            info!(user = "alice", "received");
+            debug_span!("query").in_scope(|| {});
+            warn_span!("render").in_scope(|| {
+                warn!("slow");
+            });
+        });
+    }
+}
+
+#[test]
+fn test_with_close_events() -> Result<()> {
+    let trees = fmt_json::convert(&fmt_json(FmtSpan::CLOSE, requests))?;
+
+    assert!(trees.len() == 3);
+    assert!(trees[0].event()?.message() == Some("starting"));
+
+    for (id, tree) in trees[1..].iter().enumerate() {
+        let request = tree.span()?;
+        assert!(request.name() == "request");
+        assert!(request.level() == Level::INFO);
+        assert!(request.fields()[0].key() == "id");
+        assert!(request.fields()[0].value() == id.to_string());
+        assert!(request.nodes().len() == 3);
+
+        let received = request.nodes()[0].event()?;
+        assert!(received.message() == Some("received"));
+        assert!(received.fields()[0].key() == "user");
+        assert!(received.fields()[0].value() == "alice");
+
+        // Spans without events are recovered from their close events.
+        let query = request.nodes()[1].span()?;
+        assert!(query.name() == "query");
+        assert!(query.level() == Level::DEBUG);
+        assert!(query.nodes().is_empty());
+
+        let render = request.nodes()[2].span()?;
+        assert!(render.name() == "render");
+        assert!(render.level() == Level::WARN);
+        assert!(render.nodes()[0].event()?.message() == Some("slow"));
+
+        assert!(request.total_duration() >= request.inner_duration());
+        assert!(request.inner_duration() == query.total_duration() + render.total_duration());
+        #[cfg(feature = "uuid")]
+        assert!(render.uuid() == request.uuid());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_without_close_events() -> Result<()> {
+    let output = fmt_json(FmtSpan::NONE, requests);
+
+    let mut converter = Converter::new();
+    let mut trees: Vec<Tree> = Vec::new();
+    for line in output.lines() {
+        trees.extend(converter.push(line)?);
+    }
+    // The last request is only closed at the end of the input.
+    assert!(trees.len() == 2);
+    trees.extend(converter.finish());
+    assert!(trees.len() == 3);
+
+    for tree in &trees[1..] {
+        let request = tree.span()?;
+        assert!(request.name() == "request");
+        assert!(request.nodes().len() == 2);
+        assert!(request.nodes()[0].event()?.message() == Some("received"));
+        assert!(request.nodes()[1].span()?.name() == "render");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_lifecycle_events_ignored() -> Result<()> {
+    let output = fmt_json(FmtSpan::FULL, || {
+        info_span!("outer").in_scope(|| {
+            info_span!("inner").in_scope(|| {
+                info!("hello");
+            });
+        });
+    });
+
+    let trees = fmt_json::convert(&output)?;
+    assert!(trees.len() == 1);
+
+    let outer = trees[0].span()?;
+    assert!(outer.nodes().len() == 1);
+    let inner = outer.nodes()[0].span()?;
+    assert!(inner.nodes().len() == 1);
+    assert!(inner.nodes()[0].event()?.message() == Some("hello"));
+
+    Ok(())
+}
+
+#[test]
+fn test_invalid_line() {
+    let mut converter = Converter::new();
+    assert!(converter.push("").unwrap().is_empty());
+    assert!(converter.push("not json").is_err());
+}
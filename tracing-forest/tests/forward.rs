@@ -0,0 +1,160 @@
+//! Tests for forwarding log trees as NDJSON with `Forward`.
+#![cfg(feature = "forward")]
+use serde_json::Value;
+use std::io::{BufRead, BufReader};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing_forest::processor::{self, forward::Forward};
+use tracing_forest::tree::Tree;
+use tracing_forest::{traits::*, util::*, Processor};
+use tracing_subscriber::Registry;
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+#[derive(Clone, Default)]
+struct Collect(Arc<Mutex<Vec<Tree>>>);
+
+impl Processor for Collect {
+    fn process(&self, tree: Tree) -> processor::Result {
+        self.0.lock().unwrap().push(tree);
+        Ok(())
+    }
+}
+
+fn read_lines(listener: &TcpListener, n: usize) -> Result<Vec<Value>> {
+    let (stream, _) = listener.accept()?;
+    let mut lines = BufReader::new(stream).lines();
+    (0..n)
+        .map(|_| Ok(serde_json::from_str(&lines.next().unwrap()?)?))
+        .collect()
+}
+
+#[test]
+fn test_forward_tcp() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let forward = Forward::tcp(listener.local_addr()?.to_string());
+
+    tracing::subscriber::with_default(Registry::default().with(ForestLayer::from(forward)), || {
+        info!("first");
+        info_span!("my_span").in_scope(|| {
+            info!("second");
+        });
+    });
+
+    let lines = read_lines(&listener, 2)?;
+    assert!(lines[0]["Event"]["message"] == "first");
+    assert!(lines[1]["Span"]["name"] == "my_span");
+    assert!(lines[1]["Span"]["nodes"][0]["Event"]["message"] == "second");
+
+    Ok(())
+}
+
+#[test]
+fn test_buffer_while_disconnected() -> Result<()> {
+    // Reserve a port, then close it so that connecting fails.
+    let address = TcpListener::bind("127.0.0.1:0")?.local_addr()?;
+
+    let fallback = Collect::default();
+    let forward = Forward::tcp(address.to_string())
+        .buffer_capacity(1)
+        .reconnect_interval(Duration::ZERO)
+        .or(fallback.clone());
+
+    forward.process(tree("buffered"))?;
+    forward.process(tree("rejected"))?;
+
+    {
+        let rejected = fallback.0.lock().unwrap();
+        assert!(rejected.len() == 1);
+        assert!(rejected[0].event()?.message() == Some("rejected"));
+    }
+
+    let listener = TcpListener::bind(address)?;
+    forward.process(tree("connected"))?;
+
+    let lines = read_lines(&listener, 2)?;
+    assert!(lines[0]["Event"]["message"] == "buffered");
+    assert!(lines[1]["Event"]["message"] == "connected");
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_forward_unix() -> Result<()> {
+    use std::os::unix::net::UnixListener;
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("forest.sock");
+    let listener = UnixListener::bind(&path)?;
+
+    let forward = Forward::unix(&path);
+    tracing::subscriber::with_default(Registry::default().with(ForestLayer::from(forward)), || {
+        info!("over a unix socket");
+    });
+
+    let (stream, _) = listener.accept()?;
+    let line = BufReader::new(stream).lines().next().unwrap()?;
+    let value: Value = serde_json::from_str(&line)?;
+    assert!(value["Event"]["message"] == "over a unix socket");
+
+    Ok(())
+}
+
+/// Captures a single event tree.
+fn tree(message: &'static str) -> Tree {
+    let collect = Collect::default();
+    tracing::subscriber::with_default(
+        Registry::default().with(ForestLayer::from(collect.clone())),
+        || {
+            info!("{}", message);
+        },
+    );
+    let mut trees = collect.0.lock().unwrap();
+    trees.pop().unwrap()
+}
+
+#[test]
+fn test_handshake() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let forward = Forward::tcp(listener.local_addr()?.to_string()).service("checkout");
+
+    forward.process(tree("hello"))?;
+
+    let lines = read_lines(&listener, 2)?;
+    assert!(lines[0]["service"] == "checkout");
+    assert!(lines[1]["Event"]["message"] == "hello");
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_reconnect_after_write_error() -> Result<()> {
+    use std::os::unix::net::UnixListener;
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("forest.sock");
+    let listener = UnixListener::bind(&path)?;
+
+    let forward = Forward::unix(&path).reconnect_interval(Duration::ZERO);
+    forward.process(tree("first"))?;
+
+    // The collector goes away, so the next write fails and the tree is buffered.
+    drop(listener.accept()?);
+    forward.process(tree("second"))?;
+
+    // A new connection starts with the whole buffered line.
+    forward.process(tree("third"))?;
+    let (stream, _) = listener.accept()?;
+    let lines: Vec<Value> = BufReader::new(stream)
+        .lines()
+        .take(2)
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect::<Result<_>>()?;
+    assert!(lines[0]["Event"]["message"] == "second");
+    assert!(lines[1]["Event"]["message"] == "third");
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+//! Tests for the HTML report formatter.
+#![cfg(feature = "tokio")]
+use tokio::time::Duration;
+use tracing_forest::printer::{Formatter, Html};
+use tracing_forest::util::*;
+
+#[tokio::test]
+async fn test_report() -> Result<(), Box<dyn std::error::Error>> {
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            info_span!("checkout", user = "<alice>").in_scope(|| {
+                info_span!("payment").in_scope(|| {
+                    std::thread::sleep(Duration::from_millis(10));
+                    error!(card = "4242", "card \"declined\" & refused");
+                });
+            });
+            warn!("second tree");
+        })
+        .await;
+
+    let report = Html::new().title("CI <run>").report(&logs);
+
+    assert!(report.starts_with("<!DOCTYPE html>"));
+    assert!(report.contains("<title>CI &lt;run&gt;</title>"));
+    assert!(report.contains("<input id=\"search\""));
+    assert!(report.contains("<script>"));
+
+    // Both trees are in the same document.
+    assert!(report.matches("<html").count() == 1);
+    assert!(report.contains("<span class=\"name\">checkout</span>"));
+    assert!(report.contains("<span class=\"name\">payment</span>"));
+    assert!(report.contains("second tree"));
+
+    // Spans are expanded by default, and the root span fills its bar.
+    assert!(report.contains("<details open class=\"node span info\""));
+    assert!(report.contains("style=\"width: 100.00%\""));
+
+    // Levels, tags, and fields are shown, and text is escaped.
+    assert!(report.contains("class=\"node event error\""));
+    assert!(report.contains("<span class=\"tag\">[error]</span>"));
+    assert!(report.contains("card &quot;declined&quot; &amp; refused"));
+    assert!(report.contains("<span class=\"key\">user</span>:"));
+    assert!(report.contains("&lt;alice&gt;"));
+    assert!(!report.contains("<alice>"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_formatter() -> Result<(), Box<dyn std::error::Error>> {
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            info_span!("outer").in_scope(|| {
+                info_span!("inner").in_scope(|| info!("hello"));
+            });
+        })
+        .await;
+
+    let html = Html::new().collapsed(true).fmt(&logs[0])?;
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>tracing-forest report</title>"));
+    assert!(html.contains("<details class=\"node span info\""));
+    assert!(!html.contains("<details open"));
+    assert!(html.matches("<details").count() == 2);
+    assert!(html.matches("</details>").count() == 2);
+
+    Ok(())
+}
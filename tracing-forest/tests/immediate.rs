@@ -0,0 +1,237 @@
+//! Tests for processing events immediately.
+use std::error::Error;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing_forest::printer::{Compact, Formatter, Immediate};
+use tracing_forest::processor::{self, Processor};
+use tracing_forest::tree::Tree;
+use tracing_forest::util::*;
+use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+/// Stores processed trees.
+#[derive(Clone, Default)]
+struct Store(Arc<Mutex<Vec<Tree>>>);
+
+impl Processor for Store {
+    fn process(&self, tree: Tree) -> processor::Result {
+        self.0.lock().unwrap().push(tree);
+        Ok(())
+    }
+}
+
+impl Store {
+    fn take(&self) -> Vec<Tree> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+#[derive(Debug)]
+struct Unavailable;
+
+impl fmt::Display for Unavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("alert log unavailable")
+    }
+}
+
+impl Error for Unavailable {}
+
+/// Fails to process every tree.
+struct Failing;
+
+impl Processor for Failing {
+    fn process(&self, tree: Tree) -> processor::Result {
+        Err(processor::error(tree, Box::new(Unavailable)))
+    }
+}
+
+#[test]
+fn test_immediate_processor() -> Result<(), Box<dyn Error>> {
+    let trees = Store::default();
+    let immediate = Store::default();
+    let layer = ForestLayer::from(trees.clone()).immediate(immediate.clone());
+
+    tracing::subscriber::with_default(Registry::default().with(layer), || {
+        info_span!("checkout", user = "alice").in_scope(|| {
+            info_span!("payment").in_scope(|| {
+                info!("first");
+                warn!(immediate = true, code = 402, "card declined");
+
+                // Only the immediate event has been processed so far.
+                assert!(trees.take().is_empty());
+            });
+        });
+        error!(immediate = true, "outside of any span");
+    });
+
+    let alerts = immediate.take();
+    assert!(alerts.len() == 2);
+
+    // The event is nested inside copies of its spans, without their other nodes.
+    let checkout = alerts[0].span()?;
+    assert!(checkout.name() == "checkout");
+    assert!(checkout.fields()[0].key() == "user");
+    assert!(checkout.nodes().len() == 1);
+    let payment = checkout.nodes()[0].span()?;
+    assert!(payment.name() == "payment");
+    assert!(payment.nodes().len() == 1);
+    let declined = payment.nodes()[0].event()?;
+    assert!(declined.message() == Some("card declined"));
+    assert!(declined.level() == Level::WARN);
+    #[cfg(feature = "uuid")]
+    assert!(declined.uuid() == checkout.uuid());
+
+    assert!(alerts[1].event()?.message() == Some("outside of any span"));
+
+    // By default, immediate events are written as lines marked `IMMEDIATE`.
+    let icon = tracing_forest::Tag::from(Level::WARN).icon();
+    let line = Immediate.fmt(&alerts[0])?;
+    assert!(line.ends_with(&format!(
+        "WARN     {icon} IMMEDIATE {icon} checkout > payment > card declined | code: 402\n",
+        icon = icon
+    )));
+    #[cfg(feature = "uuid")]
+    assert!(line.starts_with(&checkout.uuid().to_string()));
+
+    let icon = tracing_forest::Tag::from(Level::ERROR).icon();
+    let line = Immediate.fmt(&alerts[1])?;
+    assert!(line.ends_with(&format!(
+        "ERROR    {icon} IMMEDIATE {icon} outside of any span\n",
+        icon = icon
+    )));
+
+    // The compact formatter can be used instead.
+    let compact = Compact::new().spans(false).fmt(&alerts[0])?;
+    assert!(compact.contains("WARN     checkout > payment > card declined | code: 402\n"));
+
+    // The events still appear in the trace trees.
+    let trees = trees.take();
+    assert!(trees.len() == 2);
+    let payment = trees[0].span()?.nodes()[0].span()?;
+    assert!(payment.nodes().len() == 2);
+    assert!(payment.nodes()[1].event()?.message() == Some("card declined"));
+
+    Ok(())
+}
+
+#[test]
+fn test_immediate_fallback() -> Result<(), Box<dyn Error>> {
+    let fallback = Store::default();
+    let layer = ForestLayer::sink().immediate(Failing.or(fallback.clone()));
+
+    tracing::subscriber::with_default(Registry::default().with(layer), || {
+        info_span!("request").in_scope(|| {
+            error!(immediate = true, "disk full");
+        });
+    });
+
+    let alerts = fallback.take();
+    assert!(alerts.len() == 1);
+    assert!(alerts[0].span()?.name() == "request");
+
+    Ok(())
+}
+
+#[test]
+fn test_immediate_errors_ignored() {
+    let layer = ForestLayer::sink().immediate(Failing.or_none());
+
+    tracing::subscriber::with_default(Registry::default().with(layer), || {
+        info!(immediate = true, "dropped");
+    });
+}
+
+#[test]
+#[should_panic(expected = "Processing logs failed")]
+fn test_immediate_error_panics() {
+    let layer = ForestLayer::sink().immediate(Failing);
+
+    tracing::subscriber::with_default(Registry::default().with(layer), || {
+        info!(immediate = true, "unprocessed");
+    });
+}
+
+fn security_tag(event: &Event) -> Option<tracing_forest::Tag> {
+    match event.metadata().target() {
+        "security" => Some(
+            tracing_forest::Tag::builder()
+                .prefix("security")
+                .level(*event.metadata().level())
+                .build(),
+        ),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_immediate_policy() -> Result<(), Box<dyn Error>> {
+    use tracing_forest::immediate;
+
+    let trees = Store::default();
+    let immediate = Store::default();
+    let warnings = immediate::at_least(Level::WARN);
+    let security = immediate::tag_prefix("security");
+    let layer = ForestLayer::new(trees.clone(), security_tag)
+        .immediate(immediate.clone())
+        .immediate_if(
+            move |metadata: &tracing::Metadata, tag: Option<&tracing_forest::Tag>| {
+                warnings(metadata, tag) || security(metadata, tag)
+            },
+        );
+
+    tracing::subscriber::with_default(Registry::default().with(layer), || {
+        info_span!("request").in_scope(|| {
+            info!("ordinary");
+            info!(target: "security", "login");
+            warn!("slow");
+            error!("failed");
+            debug!(immediate = true, "explicit");
+        });
+    });
+
+    let messages: Vec<_> = immediate
+        .take()
+        .iter()
+        .map(|tree| {
+            let event = tree.span().unwrap().nodes()[0].event().unwrap();
+            event.message().unwrap().to_string()
+        })
+        .collect();
+    assert!(messages == ["login", "slow", "failed", "explicit"]);
+
+    // Every event is still recorded in the tree.
+    let trees = trees.take();
+    assert!(trees[0].span()?.nodes().len() == 5);
+
+    Ok(())
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_immediate_worker_task() -> Result<(), Box<dyn Error>> {
+    use tracing_forest::immediate;
+
+    let immediate = Store::default();
+    let logs = tracing_forest::capture()
+        .immediate(immediate.clone())
+        .immediate_if(immediate::at_least(Level::WARN))
+        .build()
+        .on(async {
+            info_span!("request").in_scope(|| {
+                info!("ordinary");
+                warn!("slow");
+            });
+        })
+        .await;
+
+    let immediate = immediate.take();
+    assert!(immediate.len() == 1);
+    let event = immediate[0].span()?.nodes()[0].event()?;
+    assert!(event.message() == Some("slow"));
+
+    // Every event is still recorded in the captured tree.
+    assert!(logs.len() == 1);
+    assert!(logs[0].span()?.nodes().len() == 2);
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+//! Tests for recording when spans open and close.
+#![cfg(feature = "tokio")]
+use std::time::Duration;
+use tracing::Instrument;
+use tracing_forest::printer::{Formatter, Pretty};
+use tracing_forest::util::*;
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+#[tokio::test]
+async fn test_lifetime() -> Result<()> {
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                info!("woke up");
+            }
+            .instrument(info_span!("waiting"))
+            .await;
+        })
+        .await;
+
+    let span = logs[0].span()?;
+    assert!(span.lifetime() >= Duration::from_millis(20));
+    assert!(span.total_duration() < span.lifetime());
+    assert!(span.idle_duration() == span.lifetime() - span.total_duration());
+
+    #[cfg(feature = "chrono")]
+    {
+        assert!(span.opened_at() == span.timestamp());
+        let lifetime = (span.closed_at() - span.opened_at()).to_std()?;
+        assert!(lifetime >= Duration::from_millis(20));
+        assert!(span.nodes()[0].event()?.timestamp() < span.closed_at());
+    }
+
+    let pretty = Pretty.fmt(&logs[0])?;
+    assert!(pretty.contains(" busy, ") && pretty.contains(" idle"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_busy_span() -> Result<()> {
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            info_span!("working").in_scope(|| {
+                std::thread::sleep(Duration::from_millis(5));
+            });
+        })
+        .await;
+
+    let span = logs[0].span()?;
+    assert!(span.lifetime() >= span.total_duration());
+    assert!(span.idle_duration() < span.total_duration());
+
+    let pretty = Pretty.fmt(&logs[0])?;
+    assert!(!pretty.contains("idle"));
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test]
+async fn test_lifetime_serde() -> Result<()> {
+    use tracing_forest::tree::Tree;
+
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            info_span!("span").in_scope(|| {});
+        })
+        .await;
+
+    let json = serde_json::to_value(&logs[0])?;
+    let span = &json["Span"];
+    assert!(span["nanos_lifetime"].as_u64() == Some(logs[0].span()?.lifetime().as_nanos() as u64));
+    #[cfg(feature = "chrono")]
+    {
+        // The span opened at its timestamp, which isn't repeated.
+        assert!(span["timestamp"].is_string() && span["closed_at"].is_string());
+        assert!(span.get("opened_at").is_none());
+    }
+
+    let tree: Tree = serde_json::from_value(json)?;
+    assert!(tree.span()?.lifetime() == logs[0].span()?.lifetime());
+
+    Ok(())
+}
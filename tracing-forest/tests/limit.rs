@@ -0,0 +1,168 @@
+//! Tests for limiting the size of recorded data.
+#![cfg(feature = "tokio")]
+use tracing_forest::limit::Limits;
+use tracing_forest::tree::Tree;
+use tracing_forest::util::*;
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+fn value<'a>(fields: &'a [tracing_forest::tree::Field], key: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|field| field.key() == key)
+        .map(|field| field.value())
+}
+
+#[tokio::test]
+async fn test_truncate() -> Result<()> {
+    let logs = tracing_forest::capture()
+        .limit(Limits::new().max_field_len(6).max_message_len(5))
+        .build()
+        .on(async {
+            info_span!("span", long = "abcdefghij", short = 1).in_scope(|| {
+                info!(emoji = "ééééé", "hello, world");
+            });
+        })
+        .await;
+
+    let span = logs[0].span()?;
+    assert!(value(span.fields(), "long") == Some("\"ab…"));
+    assert!(value(span.fields(), "short") == Some("1"));
+
+    // Truncation doesn't split characters, and the marker fits in the limit.
+    let event = span.nodes()[0].event()?;
+    assert!(value(event.fields(), "emoji") == Some("\"é…"));
+    assert!(event.message() == Some("he…"));
+    assert!(event.message().unwrap().len() <= 5);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_max_children() -> Result<()> {
+    let logs = tracing_forest::capture()
+        .limit(Limits::new().max_children(3))
+        .build()
+        .on(async {
+            info_span!("poll").in_scope(|| {
+                for i in 0..5000 {
+                    match i % 10 {
+                        0 => warn!("retrying"),
+                        1 => info_span!("attempt").in_scope(|| {}),
+                        _ => info!("waiting"),
+                    }
+                }
+            });
+        })
+        .await;
+
+    let poll = logs[0].span()?;
+    assert!(poll.nodes().len() == 4);
+
+    let summary = poll.nodes()[3].event()?;
+    assert!(summary.message() == Some("…4,498 events and 499 spans elided (499 WARN)"));
+    assert!(summary.level() == Level::WARN);
+    assert!(value(summary.fields(), "elided_events") == Some("4498"));
+    assert!(value(summary.fields(), "elided_spans") == Some("499"));
+
+    // Durations of dropped spans still count towards the parent.
+    assert!(poll.inner_duration() > std::time::Duration::ZERO);
+
+    Ok(())
+}
+
+fn count(tree: &Tree) -> usize {
+    match tree {
+        Tree::Event(_) => 1,
+        Tree::Span(span) => 1 + span.nodes().iter().map(count).sum::<usize>(),
+    }
+}
+
+#[tokio::test]
+async fn test_max_nodes() -> Result<()> {
+    let logs = tracing_forest::capture()
+        .limit(Limits::new().max_nodes(10))
+        .build()
+        .on(async {
+            info_span!("request").in_scope(|| {
+                for _ in 0..4 {
+                    info_span!("query").in_scope(|| {
+                        for _ in 0..5 {
+                            debug!("row");
+                        }
+                    });
+                }
+            });
+            info_span!("next").in_scope(|| info!("fresh tree"));
+        })
+        .await;
+
+    // Spans take their place in the tree when opened, so the second query is
+    // kept with the rows that fit, and the rest are dropped.
+    let request = logs[0].span()?;
+    assert!(request.nodes().len() == 3);
+    assert!(request.nodes()[0].span()?.nodes().len() == 5);
+    let second = request.nodes()[1].span()?;
+    assert!(second.nodes().len() == 4);
+    assert!(second.nodes()[3].event()?.message() == Some("…2 events elided"));
+    assert!(request.nodes()[2].event()?.message() == Some("…2 spans elided"));
+    assert!(count(&logs[0]) == 1 + 10 + 2);
+
+    // Each tree has its own count.
+    assert!(logs[1].span()?.nodes().len() == 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_max_children_and_nodes() -> Result<()> {
+    let logs = tracing_forest::capture()
+        .limit(Limits::new().max_children(3).max_nodes(8))
+        .build()
+        .on(async {
+            info_span!("request").in_scope(|| {
+                info_span!("lookup").in_scope(|| {
+                    for _ in 0..3 {
+                        info_span!("shard").in_scope(|| {});
+                    }
+                    info_span!("shard").in_scope(|| info!("dropped with its span"));
+                });
+                info_span!("respond").in_scope(|| {
+                    for _ in 0..3 {
+                        info!("sent");
+                    }
+                });
+            });
+        })
+        .await;
+
+    // The last shard and its event are dropped by `max_children`, so their
+    // places are given back for the events in `respond`.
+    let request = logs[0].span()?;
+    let lookup = request.nodes()[0].span()?;
+    assert!(lookup.nodes().len() == 4);
+    assert!(lookup.nodes()[3].event()?.message() == Some("…1 span elided"));
+    let respond = request.nodes()[1].span()?;
+    assert!(respond.nodes().len() == 3);
+    assert!(count(&logs[0]) == 1 + 8 + 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unlimited() -> Result<()> {
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            info_span!("span").in_scope(|| {
+                for _ in 0..1000 {
+                    info!("event");
+                }
+            });
+        })
+        .await;
+
+    assert!(logs[0].span()?.nodes().len() == 1000);
+
+    Ok(())
+}
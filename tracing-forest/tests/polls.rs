@@ -0,0 +1,79 @@
+//! Tests for recording how often spans are entered.
+#![cfg(feature = "tokio")]
+use std::time::Duration;
+use tracing::Instrument;
+use tracing_forest::printer::{Formatter, Pretty, PrettyConfig};
+use tracing_forest::util::*;
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+#[tokio::test]
+async fn test_poll_statistics() -> Result<()> {
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            async {
+                for _ in 0..3 {
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+                // Blocks the executor for the whole poll.
+                std::thread::sleep(Duration::from_millis(15));
+            }
+            .instrument(info_span!("task"))
+            .await;
+
+            info_span!("sync").in_scope(|| {});
+        })
+        .await;
+
+    let task = logs[0].span()?;
+    // At least one entry per poll. `Instrumented` may also enter the span
+    // when it's dropped.
+    assert!(task.entries() >= 4);
+    assert!(task.max_entry_duration() >= Duration::from_millis(15));
+    assert!(task.max_entry_duration() <= task.total_duration());
+
+    let sync = logs[1].span()?;
+    assert!(sync.entries() == 1);
+    assert!(sync.max_entry_duration() == sync.total_duration());
+
+    let flagged = PrettyConfig::new()
+        .slow_poll(Duration::from_millis(10))
+        .fmt(&logs[0])?;
+    assert!(flagged.contains("% ] ⚠ slow poll: "));
+
+    let relaxed = PrettyConfig::new()
+        .slow_poll(Duration::from_secs(10))
+        .fmt(&logs[0])?;
+    assert!(!relaxed.contains("slow poll"));
+
+    // Without a threshold, the output is the same as `Pretty`.
+    assert!(PrettyConfig::new().fmt(&logs[0])? == Pretty.fmt(&logs[0])?);
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test]
+async fn test_poll_statistics_serde() -> Result<()> {
+    use tracing_forest::tree::Tree;
+
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            let span = info_span!("span");
+            span.in_scope(|| {});
+            span.in_scope(|| {});
+        })
+        .await;
+
+    let json = serde_json::to_value(&logs[0])?;
+    assert!(json["Span"]["entries"] == 2);
+    assert!(json["Span"]["nanos_max_entry"].is_u64());
+
+    let tree: Tree = serde_json::from_value(json)?;
+    assert!(tree.span()?.entries() == 2);
+    assert!(tree.span()?.max_entry_duration() == logs[0].span()?.max_entry_duration());
+
+    Ok(())
+}
@@ -0,0 +1,193 @@
+//! Tests for redacting fields before processing.
+#![cfg(feature = "tokio")]
+use tracing_forest::redact::Redaction;
+use tracing_forest::tree::Tree;
+use tracing_forest::util::*;
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+fn value<'a>(fields: &'a [tracing_forest::tree::Field], key: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|field| field.key() == key)
+        .map(|field| field.value())
+}
+
+const KEY: [u8; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+
+async fn login(redaction: Redaction) -> Vec<Tree> {
+    tracing_forest::capture()
+        .redact(redaction)
+        .build()
+        .on(async {
+            info_span!("request", authorization = "Bearer abc123", path = "/login").in_scope(
+                || {
+                    info!(
+                        email = "alice@example.com",
+                        session_token = "s3cr3t",
+                        attempts = 1,
+                        "logged in"
+                    );
+                },
+            );
+            info!(email = "alice@example.com", "again");
+        })
+        .await
+}
+
+#[tokio::test]
+async fn test_mask_and_hash() -> Result<()> {
+    let logs = login(
+        Redaction::new()
+            .mask("Authorization")
+            .mask("*_token")
+            .hash_with_key("email", KEY),
+    )
+    .await;
+
+    let request = logs[0].span()?;
+    assert!(value(request.fields(), "authorization") == Some("[redacted]"));
+    assert!(value(request.fields(), "path") == Some("\"/login\""));
+
+    let event = request.nodes()[0].event()?;
+    assert!(value(event.fields(), "session_token") == Some("[redacted]"));
+    assert!(value(event.fields(), "attempts") == Some("1"));
+
+    let email = value(event.fields(), "email").unwrap();
+    assert!(email.starts_with("<hash:") && email.ends_with('>'));
+    assert!(!email.contains("alice"));
+
+    // Equal values hash the same, so they can be correlated.
+    let again = logs[1].event()?;
+    assert!(value(again.fields(), "email") == Some(email));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hash_key() -> Result<()> {
+    let hashed = |key| async move {
+        let logs = login(Redaction::new().hash_with_key("attempts", key)).await;
+        let event = logs[0].span().unwrap().nodes()[0].event().unwrap();
+        value(event.fields(), "attempts").unwrap().to_string()
+    };
+
+    // SipHash-2-4 of "1" with the key from the reference test vectors, which
+    // doesn't change between Rust versions.
+    assert!(hashed(KEY).await == "<hash:3943c8fcfccf7ce0>");
+    assert!(hashed([7; 16]).await != hashed(KEY).await);
+
+    // The key isn't shown by `Debug`.
+    let redaction = Redaction::new().hash_with_key("email", [0xab; 16]);
+    assert!(!format!("{:?}", redaction).contains("171"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_first_rule_applies() -> Result<()> {
+    let logs = login(
+        Redaction::new()
+            .hash_with_key("email", KEY)
+            .mask("*")
+            .mask_with("***"),
+    )
+    .await;
+
+    let request = logs[0].span()?;
+    assert!(value(request.fields(), "path") == Some("***"));
+    let event = request.nodes()[0].event()?;
+    assert!(value(event.fields(), "email").is_some_and(|value| value.starts_with("<hash:")));
+    assert!(value(event.fields(), "attempts") == Some("***"));
+    assert!(event.message() == Some("logged in"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_glob() -> Result<()> {
+    let logs = login(Redaction::new().mask("?ath").mask("sess*tok?n")).await;
+
+    let request = logs[0].span()?;
+    assert!(value(request.fields(), "path") == Some("[redacted]"));
+    assert!(value(request.fields(), "authorization") == Some("\"Bearer abc123\""));
+    let event = request.nodes()[0].event()?;
+    assert!(value(event.fields(), "session_token") == Some("[redacted]"));
+
+    // Wildcards match whole characters, not bytes.
+    let logs = tracing_forest::capture()
+        .redact(Redaction::new().mask("cl?").mask("stra?e"))
+        .build()
+        .on(async {
+            info!("clé" = 1, "straße" = 2, "clés" = 3, "ok");
+        })
+        .await;
+    let event = logs[0].event()?;
+    assert!(value(event.fields(), "clé") == Some("[redacted]"));
+    assert!(value(event.fields(), "straße") == Some("[redacted]"));
+    assert!(value(event.fields(), "clés") == Some("3"));
+
+    Ok(())
+}
+
+#[cfg(feature = "regex")]
+#[tokio::test]
+async fn test_regex() -> Result<()> {
+    use regex::Regex;
+
+    let logs = tracing_forest::capture()
+        .redact(
+            Redaction::new()
+                .mask_regex(Regex::new("^(password|secret)$")?)
+                .hash_regex_with_key(Regex::new("mail")?, KEY)
+                .mask_messages(Regex::new(r"Bearer \S+")?),
+        )
+        .build()
+        .on(async {
+            info!(
+                password = "hunter2",
+                secret_id = 7,
+                email = "bob@example.com",
+                "got Bearer abc.def and Bearer xyz"
+            );
+        })
+        .await;
+
+    let event = logs[0].event()?;
+    assert!(value(event.fields(), "password") == Some("[redacted]"));
+    assert!(value(event.fields(), "secret_id") == Some("7"));
+    assert!(value(event.fields(), "email").is_some_and(|value| value.starts_with("<hash:")));
+    assert!(event.message() == Some("got [redacted] and [redacted]"));
+
+    Ok(())
+}
+
+#[test]
+fn test_immediate_redacted() {
+    use std::sync::{Arc, Mutex};
+    use tracing_forest::processor::{self, Processor};
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    #[derive(Clone, Default)]
+    struct Store(Arc<Mutex<Vec<Tree>>>);
+
+    impl Processor for Store {
+        fn process(&self, tree: Tree) -> processor::Result {
+            self.0.lock().unwrap().push(tree);
+            Ok(())
+        }
+    }
+
+    let immediate = Store::default();
+    let layer = ForestLayer::sink()
+        .immediate(immediate.clone())
+        .redact(Redaction::new().mask("password"));
+
+    tracing::subscriber::with_default(Registry::default().with(layer), || {
+        warn!(immediate = true, password = "hunter2", "changed");
+    });
+
+    let alerts = immediate.0.lock().unwrap();
+    let event = alerts[0].event().unwrap();
+    assert!(value(event.fields(), "password") == Some("[redacted]"));
+}
@@ -0,0 +1,168 @@
+//! Tests for writing log trees to rotating files with `RollingFile`.
+#![cfg(feature = "rolling-file")]
+use std::fs;
+use std::io::Write;
+use tracing_forest::printer::{RollingFile, Rotation};
+use tracing_subscriber::fmt::MakeWriter;
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+fn file_names(dir: &std::path::Path) -> Result<Vec<String>> {
+    let mut names = fs::read_dir(dir)?
+        .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+        .collect::<Result<Vec<_>>>()?;
+    names.sort();
+    Ok(names)
+}
+
+#[test]
+fn test_rotate_by_size() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let rolling = RollingFile::builder(dir.path())
+        .pattern("test.{index}.log")
+        .max_bytes(10)
+        .build()?;
+
+    for _ in 0..3 {
+        rolling.make_writer().write_all(b"0123456789")?;
+    }
+
+    assert!(file_names(dir.path())? == ["test.0.log", "test.1.log", "test.2.log"]);
+    assert!(fs::read_to_string(dir.path().join("test.1.log"))? == "0123456789");
+
+    Ok(())
+}
+
+#[test]
+fn test_retention() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("unrelated.txt"), "keep me")?;
+
+    let rolling = RollingFile::builder(dir.path())
+        .pattern("test.{index}.log")
+        .max_bytes(1)
+        .max_files(2)
+        .build()?;
+
+    for _ in 0..5 {
+        rolling.make_writer().write_all(b"x")?;
+    }
+
+    assert!(rolling.current_path() == dir.path().join("test.4.log"));
+    assert!(file_names(dir.path())? == ["test.2.log", "test.3.log", "test.4.log", "unrelated.txt"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_resume_existing() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("test.0.log"), "full")?;
+    fs::write(dir.path().join("test.1.log"), "a")?;
+
+    let rolling = RollingFile::builder(dir.path())
+        .pattern("test.{index}.log")
+        .max_bytes(4)
+        .build()?;
+
+    rolling.make_writer().write_all(b"b")?;
+
+    assert!(fs::read_to_string(dir.path().join("test.1.log"))? == "ab");
+
+    Ok(())
+}
+
+#[test]
+fn test_pattern_placeholders() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+
+    let error = RollingFile::builder(dir.path())
+        .pattern("test.{index}.log")
+        .rotation(Rotation::Daily)
+        .build()
+        .unwrap_err();
+    assert!(error.kind() == std::io::ErrorKind::InvalidInput);
+    assert!(error.to_string().contains("{date}"));
+
+    let error = RollingFile::builder(dir.path())
+        .pattern("test.{date}.log")
+        .max_bytes(10)
+        .build()
+        .unwrap_err();
+    assert!(error.kind() == std::io::ErrorKind::InvalidInput);
+    assert!(error.to_string().contains("{index}"));
+
+    // Nothing is created for an invalid pattern.
+    assert!(file_names(dir.path())?.is_empty());
+
+    Ok(())
+}
+
+mod clock {
+    use chrono::{DateTime, TimeZone, Utc};
+    use std::sync::atomic::{AtomicI64, Ordering};
+
+    static HOURS: AtomicI64 = AtomicI64::new(0);
+
+    /// Returns midnight on 2024-01-01, plus the hours advanced so far.
+    pub fn now() -> DateTime<Utc> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        start + chrono::Duration::hours(HOURS.load(Ordering::Relaxed))
+    }
+
+    pub fn advance(hours: i64) {
+        HOURS.fetch_add(hours, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn test_rotate_by_time() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let rolling = RollingFile::builder(dir.path())
+        .pattern("test.{date}.{index}.log")
+        .rotation(Rotation::Daily)
+        .max_bytes(100)
+        .clock(clock::now)
+        .build()?;
+
+    rolling.make_writer().write_all(b"monday")?;
+    clock::advance(1);
+    rolling.make_writer().write_all(b", still monday")?;
+    clock::advance(24);
+    rolling.make_writer().write_all(b"tuesday")?;
+
+    assert!(rolling.current_path() == dir.path().join("test.2024-01-02.0.log"));
+    assert!(file_names(dir.path())? == ["test.2024-01-01.0.log", "test.2024-01-02.0.log"]);
+    assert!(
+        fs::read_to_string(dir.path().join("test.2024-01-01.0.log"))? == "monday, still monday"
+    );
+    assert!(fs::read_to_string(dir.path().join("test.2024-01-02.0.log"))? == "tuesday");
+
+    Ok(())
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_compress() -> Result<()> {
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    let dir = tempfile::tempdir()?;
+    let rolling = RollingFile::builder(dir.path())
+        .pattern("test.{index}.log")
+        .max_bytes(5)
+        .compress(true)
+        .build()?;
+
+    rolling.make_writer().write_all(b"hello")?;
+    rolling.make_writer().write_all(b"world")?;
+
+    assert!(file_names(dir.path())? == ["test.0.log.gz", "test.1.log"]);
+
+    let mut decoded = String::new();
+    GzDecoder::new(fs::File::open(dir.path().join("test.0.log.gz"))?)
+        .read_to_string(&mut decoded)?;
+    assert!(decoded == "hello");
+
+    Ok(())
+}
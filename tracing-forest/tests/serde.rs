@@ -0,0 +1,70 @@
+//! Tests for serializing and deserializing log trees.
+#![cfg(all(feature = "tokio", feature = "serde"))]
+use tracing_forest::tree::Tree;
+use tracing_forest::{util::*, Tag};
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+fn tag(event: &Event) -> Option<Tag> {
+    match event.metadata().target() {
+        "security" => Some(
+            Tag::builder()
+                .prefix("security")
+                .suffix("critical")
+                .icon('🔐')
+                .build(),
+        ),
+        "admin" => Some(
+            Tag::builder()
+                .prefix("admin")
+                .level(*event.metadata().level())
+                .build(),
+        ),
+        _ => None,
+    }
+}
+
+#[tokio::test]
+async fn test_round_trip() -> Result<()> {
+    let logs = tracing_forest::capture()
+        .set_tag(tag)
+        .build()
+        .on(async {
+            info_span!("my_span", answer = 42).in_scope(|| {
+                info!(target: "admin", status = "ok", "admin info");
+                warn!(target: "security", "breach");
+                info_span!("inner").in_scope(|| {
+                    debug!("nested");
+                });
+            });
+        })
+        .await;
+
+    let json = serde_json::to_string(&logs[0])?;
+    let tree: Tree = serde_json::from_str(&json)?;
+
+    // The deserialized tree serializes to the same JSON.
+    assert!(serde_json::to_string(&tree)? == json);
+
+    let span = tree.span()?;
+    assert!(span.name() == "my_span");
+    assert!(span.uuid() == logs[0].span()?.uuid());
+    assert!(span.timestamp() == logs[0].span()?.timestamp());
+    assert!(span.total_duration() == logs[0].span()?.total_duration());
+    assert!(span.level() == Level::INFO);
+    assert!(span.nodes().len() == 3);
+
+    let admin = span.nodes()[0].event()?;
+    assert!(admin.message() == Some("admin info"));
+    assert!(admin.fields()[0].key() == "status");
+    assert!(admin.fields()[0].value() == "\"ok\"");
+    assert!(admin.tag().unwrap().icon() == 'ｉ');
+
+    let security = span.nodes()[1].event()?;
+    assert!(security.tag().unwrap().to_string() == "security.critical");
+
+    let inner = span.nodes()[2].span()?;
+    assert!(inner.nodes()[0].event()?.level() == Level::DEBUG);
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+//! Tests for storing trees in SQLite.
+#![cfg(all(feature = "sqlite", feature = "tokio"))]
+use tracing_forest::processor::sqlite::rusqlite::Connection;
+use tracing_forest::processor::sqlite::Sqlite;
+use tracing_forest::processor::Processor;
+use tracing_forest::util::*;
+
+type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+async fn checkout() -> Result<Sqlite> {
+    let logs = tracing_forest::capture()
+        .build()
+        .on(async {
+            info_span!("checkout", user = "alice").in_scope(|| {
+                info_span!("db.query", table = "carts").in_scope(|| {});
+                info_span!("payment").in_scope(|| {
+                    info_span!("db.query", table = "cards").in_scope(|| {
+                        error!(code = 402, "card declined");
+                    });
+                });
+            });
+            warn!("outside of any span");
+        })
+        .await;
+
+    let sqlite = Sqlite::from_connection(Connection::open_in_memory()?)?;
+    for tree in logs {
+        sqlite.process(tree)?;
+    }
+    Ok(sqlite)
+}
+
+#[tokio::test]
+async fn test_spans() -> Result<()> {
+    let sqlite = checkout().await?;
+    let connection = sqlite.connection();
+
+    let trees: i64 = connection.query_row("SELECT count(*) FROM trees", [], |row| row.get(0))?;
+    assert!(trees == 2);
+
+    let mut statement = connection.prepare(
+        "SELECT spans.path, spans.depth, parents.name \
+         FROM spans LEFT JOIN spans AS parents ON parents.id = spans.parent_id \
+         ORDER BY spans.id",
+    )?;
+    let spans = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<core::result::Result<Vec<(String, i64, Option<String>)>, _>>()?;
+
+    assert!(
+        spans
+            == [
+                ("checkout".to_string(), 0, None),
+                (
+                    "checkout/db.query".to_string(),
+                    1,
+                    Some("checkout".to_string())
+                ),
+                (
+                    "checkout/payment".to_string(),
+                    1,
+                    Some("checkout".to_string())
+                ),
+                (
+                    "checkout/payment/db.query".to_string(),
+                    2,
+                    Some("payment".to_string())
+                ),
+            ]
+    );
+
+    let consistent: bool = connection.query_row(
+        "SELECT min(total_nanos = inner_nanos + base_nanos) FROM spans",
+        [],
+        |row| row.get(0),
+    )?;
+    assert!(consistent);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_events_and_fields() -> Result<()> {
+    let sqlite = checkout().await?;
+    let connection = sqlite.connection();
+
+    let (path, level, tag, message): (String, String, Option<String>, String) = connection
+        .query_row(
+            "SELECT spans.path, events.level, events.tag, events.message \
+             FROM events JOIN spans ON spans.id = events.span_id",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+    assert!(path == "checkout/payment/db.query");
+    assert!(level == "ERROR");
+    assert!(tag.is_none());
+    assert!(message == "card declined");
+
+    let orphan: Option<i64> = connection.query_row(
+        "SELECT span_id FROM events WHERE message = 'outside of any span'",
+        [],
+        |row| row.get(0),
+    )?;
+    assert!(orphan.is_none());
+
+    let code: String = connection.query_row(
+        "SELECT value FROM fields JOIN events ON events.id = fields.event_id WHERE key = 'code'",
+        [],
+        |row| row.get(0),
+    )?;
+    assert!(code == "402");
+
+    let tables: Vec<String> = connection
+        .prepare(
+            "SELECT spans.path FROM spans JOIN fields ON fields.span_id = spans.id \
+             WHERE fields.key = 'table' ORDER BY spans.id",
+        )?
+        .query_map([], |row| row.get(0))?
+        .collect::<core::result::Result<_, _>>()?;
+    assert!(tables == ["checkout/db.query", "checkout/payment/db.query"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reopen() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("traces.db");
+
+    for _ in 0..2 {
+        let sqlite = Sqlite::open(&path)?;
+        let logs = tracing_forest::capture()
+            .build()
+            .on(async {
+                info_span!("request").in_scope(|| info!("handled"));
+            })
+            .await;
+        for tree in logs {
+            sqlite.process(tree)?;
+        }
+    }
+
+    let sqlite = Sqlite::open(&path)?;
+    let requests: i64 = sqlite.connection().query_row(
+        "SELECT count(*) FROM spans WHERE name = 'request'",
+        [],
+        |row| row.get(0),
+    )?;
+    assert!(requests == 2);
+
+    Ok(())
+}